@@ -11,10 +11,13 @@ struct Opt {
     /// Target language.
     language: Language,
 
-    /// Path to a `.rs` file.
+    /// Path to a `.rs` file, or a `ridl1` file with `--from ridl1`.
     /// Please note that only certain subset will be supported.
     #[structopt(long="in")]
     input: Option<String>,
+    /// Input format. (`rust` or `ridl1`)
+    #[structopt(long="from", default_value="rust")]
+    source: Source,
     /// Path to write generated code code.
     /// RIDL won't produce target code if this is not designated.
     /// Then effectively performs only lint stage.
@@ -28,9 +31,22 @@ struct Opt {
     skippings: Vec<String>,
 
     /// Rename field names to one of these cases.
-    /// For now the only supported option is `camel` which means `camelCase`.
+    /// Accepts `serde` rule names (e.g. `camelCase`) or short names (e.g. `camel`).
+    /// Applied only to types without `#[serde(rename_all="...")]`.
     #[structopt(long="rename")]
     rename: Option<model::rename::Rule>,
+
+    /// Wire encoding of 64-bit integers. (`number`, `bigint` or `string`)
+    /// Applied only to members without `#[int64(...)]`.
+    #[structopt(long="int64")]
+    int64: Option<model::KInt64Encoding>,
+}
+#[derive(strum_macros::EnumString)]
+enum Source {
+    #[strum(serialize="rust")]
+    Rust,
+    #[strum(serialize="ridl1")]
+    RIDL1,
 }
 #[derive(strum_macros::EnumString)]
 enum Language {
@@ -40,8 +56,25 @@ enum Language {
     OpenAPI3,
     #[strum(serialize="swift5")]
     Swift5,
+    #[strum(serialize="swift5-client")]
+    Swift5Client,
     #[strum(serialize="typescript4")]
     TypeScript4,
+    #[strum(serialize="typescript4-client")]
+    TypeScript4Client,
+    #[strum(serialize="rust")]
+    Rust,
+    #[strum(serialize="rust-server")]
+    RustServer,
+    #[strum(serialize="rust-client")]
+    RustClient,
+}
+impl Language {
+    /// Rust code refers to Rust names, and keeps members skipped on wire.
+    /// `ridl1` export keeps them too, so it can be read back as the source.
+    fn keeps_rust_names(&self) -> bool {
+        matches!(self, Language::RIDL1 | Language::Rust | Language::RustServer | Language::RustClient)
+    }
 }
 
 fn main() {
@@ -61,20 +94,38 @@ fn run() -> Result<()> {
         Some(x) => read_all_from_file(&x)?,
     };
 
-    let ast = syn::parse_str::<syn::File>(&src)?;
-    let mut model = scan::scan(&ast)?;
+    let mut model = match &opt.source {
+        Source::Rust => scan::scan(&syn::parse_str::<syn::File>(&src)?)?,
+        Source::RIDL1 => {
+            let model = serde_yaml::from_str::<model::KMod>(&src)?;
+            scan::check(&model)?;
+            model
+        },
+    };
     model.retain_only_non_skipping_items(&opt.skippings);
-    model.rename(&model::rename::Options { 
-        case: opt.rename,
-        variant: opt.rename,
-        field: opt.rename,
-    });
+    if !opt.language.keeps_rust_names() {
+        model.retain_only_non_skipped_members();
+        model.rename(&model::rename::Options { 
+            case: opt.rename,
+            variant: opt.rename,
+            field: opt.rename,
+        });
+        model.encode_int64(opt.int64);
+    }
+    for log in model.lint() {
+        eprintln!("warning: {}", log);
+    }
     
     let dst = match &opt.language {
         Language::RIDL1 => render::ridl1::render_ridl1(&model)?,
         Language::OpenAPI3 => render::openapi3::render_openapi3(&model)?,
         Language::Swift5 => render::swift5::render_swift5(&model)?,
+        Language::Swift5Client => render::swift5::render_swift5_client(&model)?,
         Language::TypeScript4 => render::typescript4::render_typescript4(&model)?,
+        Language::TypeScript4Client => render::typescript4::render_typescript4_client(&model)?,
+        Language::Rust => render::rust::render_rust(&model)?,
+        Language::RustServer => render::rust::render_rust_server(&model)?,
+        Language::RustClient => render::rust::render_rust_client(&model)?,
     };
 
     match &opt.output {
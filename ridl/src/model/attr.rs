@@ -1,4 +1,6 @@
 use serde::{Serialize,Deserialize};
+use super::rename::Rule;
+use super::KInt64Encoding;
 
 #[derive(Serialize,Deserialize)]
 #[derive(Eq,PartialEq)]
@@ -8,6 +10,59 @@ pub struct KAttrs {
     #[serde(default)]
     #[serde(skip_serializing_if="is_default")]
     pub rest: Vec<KAttrREST>,
+    #[serde(default)]
+    #[serde(skip_serializing_if="is_default")]
+    pub serde: Vec<KAttrSerde>,
+    /// `#[int64(...)]` on a field, variant or new-type.
+    #[serde(default)]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub int64: Option<KInt64Encoding>,
+}
+impl KAttrs {
+    pub fn serde_rename(&self) -> Option<&str> {
+        self.serde.iter().find_map(|x| if let KAttrSerde::Rename(x) = x { Some(x.as_str()) } else { None })
+    }
+    pub fn serde_rename_all(&self) -> Option<Rule> {
+        self.serde.iter().find_map(|x| if let KAttrSerde::RenameAll(x) = x { Some(*x) } else { None })
+    }
+    pub fn has_serde_skip(&self) -> bool {
+        self.serde.contains(&KAttrSerde::Skip)
+    }
+    pub fn has_serde_default(&self) -> bool {
+        self.serde.contains(&KAttrSerde::Default)
+    }
+    pub fn has_serde_skip_serializing_if(&self) -> bool {
+        self.serde.iter().any(|x| matches!(x, KAttrSerde::SkipSerializingIf(_)))
+    }
+    pub fn has_serde_with(&self) -> bool {
+        self.serde.iter().any(|x| matches!(x, KAttrSerde::With(_)))
+    }
+    /// HTTP method of an endpoint. (e.g. `GET`)
+    pub fn rest_method(&self) -> Option<&str> {
+        self.rest.iter().find_map(|x| if let KAttrREST::FnMethod(x) = x { Some(x.as_str()) } else { None })
+    }
+    /// HTTP path template of an endpoint. (e.g. `/orders/{id}`)
+    pub fn rest_path(&self) -> Option<&str> {
+        self.rest.iter().find_map(|x| if let KAttrREST::FnPath(x) = x { Some(x.as_str()) } else { None })
+    }
+    /// HTTP status code of a response variant.
+    pub fn rest_status(&self) -> Option<i64> {
+        self.rest.iter().find_map(|x| if let KAttrREST::Status(x) = x { Some(*x) } else { None })
+    }
+    /// HTTP status code of a response variant, or `default` for catch-all variant.
+    pub fn rest_status_key(&self) -> Option<String> {
+        if self.rest.contains(&KAttrREST::DefaultStatus) { return Some("default".to_string()) }
+        self.rest_status().map(|x| x.to_string())
+    }
+    /// Names of `#[header("...")]` on a field or response variant.
+    /// `None` for `#[header]` without name.
+    pub fn rest_headers(&self) -> Vec<Option<&str>> {
+        self.rest.iter().filter_map(|x| if let KAttrREST::HeaderParam(x) = x { Some(x.as_deref()) } else { None }).collect()
+    }
+    /// MIME-Type of a body field or response variant.
+    pub fn rest_mime(&self) -> Option<&str> {
+        self.rest.iter().find_map(|x| if let KAttrREST::MIME(x) = x { Some(x.as_str()) } else { None })
+    }
 }
 
 #[derive(Serialize,Deserialize)]
@@ -21,14 +76,57 @@ pub enum KAttrREST {
     MessageOut,
     PathParam,
     QueryParam,
+    /// `#[header]` or `#[header("X-Name")]` on a field.
+    /// Wire name is the field name unless specified.
+    /// `#[header("X-Name")]` on a response variant declares a response header.
+    HeaderParam(Option<String>),
+    /// `#[cookie]` or `#[cookie("name")]` on a field.
+    /// Wire name is the field name unless specified.
+    CookieParam(Option<String>),
     BodyParam,
     Status(i64),
+    /// `#[status(default)]` on a catch-all response variant.
+    DefaultStatus,
     MIME(String),
 }
 
 pub type KAttrRESTHTTPMethod = String;
 pub type KAttrRESTHTTPPath = String;
 
-fn is_default(x:&Vec<KAttrREST>) -> bool {
-    *x == Vec::default()
-}
\ No newline at end of file
+/// `serde` attributes which affect serialization form.
+/// Only attributes RIDL can reproduce in other languages are defined here.
+/// Scanner rejects any other `serde` attributes.
+#[derive(Serialize,Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Clone)]
+#[derive(Debug)]
+pub enum KAttrSerde {
+    /// `#[serde(rename="...")]` on a field, variant or case.
+    Rename(String),
+    /// `#[serde(rename_all="...")]` on a struct or enum.
+    RenameAll(Rule),
+    /// `#[serde(skip)]` on a field, variant or case.
+    Skip,
+    /// `#[serde(default)]` or `#[serde(default="...")]` on a field.
+    /// Field can be omitted on deserialization.
+    Default,
+    /// `#[serde(skip_serializing_if="...")]` on a field.
+    /// Field can be omitted on serialization.
+    SkipSerializingIf(String),
+    /// `#[serde(with="...")]` on a field.
+    /// Accepted only with `#[int64(string)]` as RIDL cannot know what the module does.
+    With(String),
+    /// `#[serde(tag="...")]` on an enum.
+    /// Scanner moves this into `KSumType::serialization`.
+    Tag(String),
+    /// `#[serde(content="...")]` on an enum.
+    /// Scanner moves this into `KSumType::serialization`.
+    Content(String),
+    /// `#[serde(untagged)]` on an enum.
+    /// Scanner moves this into `KSumType::serialization`.
+    Untagged,
+}
+
+fn is_default<T:Default + PartialEq>(x:&T) -> bool {
+    *x == T::default()
+}
@@ -0,0 +1,99 @@
+//! Generic type definitions and applications.
+//!
+//! Some targets (e.g. OpenAPI3) have no generics.
+//! For them, generic definitions need to be instantiated for each set of type arguments.
+
+use crate::prelude::*;
+use super::*;
+
+impl KItem {
+    pub fn type_params(&self) -> &[String] {
+        use KItem::*;
+        match self {
+            New(x) => &x.type_params,
+            Nominal(x) => &x.type_params,
+            Sum(x) => &x.type_params,
+            Prod(x) => &x.type_params,
+            _ => &[],
+        }
+    }
+    /// Makes a concrete copy of a generic definition.
+    /// Type parameters will be replaced with `args`, and name will be replaced with `name`.
+    /// Returns `None` if this is not a generic definition.
+    pub fn instantiate(&self, name:&str, args:&[KType]) -> Option<KItem> {
+        let mut params = Map::new();
+        for (p,a) in self.type_params().iter().zip(args.iter()) {
+            params.insert(p.as_str(), a);
+        }
+        let mut x = self.clone();
+        match &mut x {
+            KItem::New(x) => {
+                x.name = name.to_string();
+                x.type_params = Vec::new();
+                x.origin = x.origin.substitute(&params);
+            },
+            KItem::Nominal(x) => {
+                x.name = name.to_string();
+                x.type_params = Vec::new();
+                x.origin = x.origin.substitute(&params);
+            },
+            KItem::Sum(x) => {
+                x.name = name.to_string();
+                x.type_params = Vec::new();
+                for v in x.variants.iter_mut() { v.for_each_type_mut(&mut |x| *x = x.substitute(&params)) }
+            },
+            KItem::Prod(x) => {
+                x.name = name.to_string();
+                x.type_params = Vec::new();
+                for f in x.fields.iter_mut() { f.content = f.content.substitute(&params) }
+            },
+            _ => return None,
+        }
+        Some(x)
+    }
+    /// Visits all type expressions stored in this item and its subitems.
+    pub fn for_each_type(&self, f:&mut impl FnMut(&KType)) {
+        use KItem::*;
+        match self {
+            Mod(x) => for x in x.items.iter() { x.for_each_type(f) },
+            New(x) => f(&x.origin),
+            Nominal(x) => f(&x.origin),
+            Enum(_) => (),
+            Sum(x) => for x in x.variants.iter() { x.for_each_type(f) },
+            Prod(x) => for x in x.fields.iter() { f(&x.content) },
+            Func(x) => { f(&x.input); f(&x.output) },
+            Service(x) => for x in x.operations.iter() { f(&x.input); f(&x.output) },
+        }
+    }
+}
+
+type Params<'a> = Map<&'a str, &'a KType>;
+
+impl KType {
+    fn substitute(&self, params:&Params) -> KType {
+        use KType::*;
+        match self {
+            Def(x) if params.contains_key(x.as_str()) => params[x.as_str()].clone(),
+            Vector(x) => Vector(Box::new(x.substitute(params))),
+            Option(x) => Option(Box::new(x.substitute(params))),
+            Map(k,v) => Map(Box::new(k.substitute(params)), Box::new(v.substitute(params))),
+            Set(x) => Set(Box::new(x.substitute(params))),
+            Boxed(x) => Boxed(Box::new(x.substitute(params))),
+            App(name,args) => App(name.clone(), args.iter().map(|x| x.substitute(params)).collect()),
+            _ => self.clone(),
+        }
+    }
+    /// Visits all generic applications in this type expression.
+    pub fn for_each_app(&self, f:&mut impl FnMut(&str, &[KType])) {
+        use KType::*;
+        match self {
+            Vector(x) | Option(x) | Set(x) | Boxed(x) => x.for_each_app(f),
+            Map(k,v) => { k.for_each_app(f); v.for_each_app(f) },
+            App(name,args) => {
+                f(name, args);
+                for x in args.iter() { x.for_each_app(f) }
+            },
+            Def(_) | Int64(_,_) | Prim(_) | Unit | Never | Unknown => (),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Wire encoding of 64-bit integers.
+//!
+//! JavaScript `number` cannot represent 64-bit integers precisely.
+//! Encoding can be chosen for whole schema, and overridden by `#[int64(...)]` on members.
+
+use super::*;
+
+impl KMod {
+    /// Replaces all 64-bit integer types with `KType::Int64`.
+    /// Types without an encoding from `#[int64(...)]` or `default` are left as is.
+    pub fn encode_int64(&mut self, default:Option<KInt64Encoding>) {
+        for item in self.items.iter_mut() {
+            item.encode_int64(default);
+        }
+    }
+}
+
+impl KItem {
+    fn encode_int64(&mut self, default:Option<KInt64Encoding>) {
+        use KItem::*;
+        match self {
+            Mod(x) => x.encode_int64(default),
+            New(x) => x.origin.encode_int64(x.attrs.int64.or(default)),
+            Nominal(x) => x.origin.encode_int64(x.attrs.int64.or(default)),
+            Enum(_) => (),
+            Sum(x) => for v in x.variants.iter_mut() {
+                let default = v.attrs.int64.or(default);
+                match &mut v.form {
+                    KSumTypeVariantForm::Struct(fields) => for f in fields.iter_mut() { f.content.encode_int64(f.attrs.int64.or(default)) },
+                    _ => v.for_each_type_mut(&mut |x| x.encode_int64(default)),
+                }
+            },
+            Prod(x) => for f in x.fields.iter_mut() { f.content.encode_int64(f.attrs.int64.or(default)) },
+            Func(x) => {
+                x.input.encode_int64(default);
+                x.output.encode_int64(default);
+            },
+            Service(x) => for x in x.operations.iter_mut() {
+                let default = x.attrs.int64.or(default);
+                x.input.encode_int64(default);
+                x.output.encode_int64(default);
+            },
+        }
+    }
+}
+
+impl KType {
+    fn encode_int64(&mut self, encoding:Option<KInt64Encoding>) {
+        use KType::*;
+        let encoding = match encoding { None => return, Some(x) => x };
+        match self {
+            Prim(x) if x.is_64bit() => *self = Int64(x.clone(), encoding),
+            Vector(x) | Option(x) | Set(x) | Boxed(x) => x.encode_int64(Some(encoding)),
+            // Map keys are always strings on wire.
+            Map(_,v) => v.encode_int64(Some(encoding)),
+            App(_,args) => for x in args.iter_mut() { x.encode_int64(Some(encoding)) },
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Finds suspicious patterns which are legal but likely to cause problems.
+//! Unlike scanning errors, these are warnings and do not stop code-gen.
+
+use crate::prelude::*;
+use super::*;
+use super::log::Log;
+
+impl KMod {
+    /// Collects warnings over whole module.
+    pub fn lint(&self) -> Vec<Log> {
+        let mut defs = Map::new();
+        self.collect_defs(&mut defs);
+        let mut logs = Vec::new();
+        self.lint_items(&defs, &mut logs);
+        logs
+    }
+    fn lint_items(&self, defs:&Defs, logs:&mut Vec<Log>) {
+        for item in self.items.iter() {
+            match item {
+                KItem::Mod(x) => x.lint_items(defs, logs),
+                KItem::Sum(x) => x.lint(defs, logs),
+                _ => (),
+            }
+            item.lint_int64(logs);
+        }
+    }
+}
+
+type Defs<'a> = Map<&'a str, &'a KItem>;
+
+impl KItem {
+    fn lint_int64(&self, logs:&mut Vec<Log>) {
+        let is_lossy = |x:&KType| matches!(x, KType::Int64(_,KInt64Encoding::Number));
+        let mut check = |span:KSpan, x:&KType| if x.any(&is_lossy) {
+            let message = "64-bit integer encoded as JSON number loses precision beyond 2^53 in JavaScript";
+            logs.push(Log { span: span, message: PString::new(message.to_string()) });
+        };
+        match self {
+            KItem::New(x) => check(x.span, &x.origin),
+            KItem::Nominal(x) => check(x.span, &x.origin),
+            KItem::Sum(x) => for v in x.variants.iter() { v.for_each_type(&mut |x| check(v.span, x)) },
+            KItem::Prod(x) => for f in x.fields.iter() { check(f.span, &f.content) },
+            KItem::Service(x) => for x in x.operations.iter() { check(x.span, &x.input); check(x.span, &x.output) },
+            _ => (),
+        }
+    }
+}
+
+impl KSumType {
+    fn lint(&self, defs:&Defs, logs:&mut Vec<Log>) {
+        if self.serialization != KSumTypeSerializationForm::Untagged { return }
+        let shapes = self.variants.iter().map(|x| x.shape(defs)).collect::<Vec<_>>();
+        for (i,a) in shapes.iter().enumerate() {
+            for (j,b) in shapes.iter().enumerate().skip(i+1) {
+                if a.accepts(b) {
+                    let message = format!(
+                        "untagged sum-type variant `{}` cannot be told apart from earlier variant `{}` (it will never be decoded)",
+                        self.variants[j].name,
+                        self.variants[i].name);
+                    logs.push(Log { span: self.variants[j].span, message: PString::new(message) });
+                }
+            }
+        }
+    }
+}
+
+/// Rough shape of a serialized value.
+enum Shape {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    /// Names of required properties and all properties.
+    /// `None` for objects with unknown properties.
+    Object(Option<(Vec<String>, Vec<String>)>),
+    Unknown,
+}
+impl Shape {
+    /// Whether a decoder for `self` would also accept values of shape `other`.
+    fn accepts(&self, other:&Shape) -> bool {
+        use Shape::*;
+        match (self, other) {
+            (Null, Null) | (Bool, Bool) | (Number, Number) | (String, String) | (Array, Array) => true,
+            (Object(Some((reqs,_))), Object(Some((_,props)))) => reqs.iter().all(|x| props.contains(x)),
+            (Object(_), Object(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl KSumTypeVariant {
+    /// Shape of untagged variant.
+    fn shape(&self, defs:&Defs) -> Shape {
+        match &self.form {
+            KSumTypeVariantForm::Newtype => self.content.shape(defs),
+            KSumTypeVariantForm::Unit => Shape::Null,
+            KSumTypeVariantForm::Tuple(_) => Shape::Array,
+            KSumTypeVariantForm::Struct(fields) => Shape::Object(Some(object_keys(fields))),
+        }
+    }
+}
+
+fn object_keys(fields:&[KProdTypeField]) -> (Vec<String>, Vec<String>) {
+    let reqs = fields.iter().filter(|x| !x.is_omittable()).map(|x| x.name.clone()).collect();
+    let props = fields.iter().map(|x| x.name.clone()).collect();
+    (reqs, props)
+}
+
+impl KType {
+    fn shape(&self, defs:&Defs) -> Shape {
+        use KPrimType::*;
+        match self {
+            KType::Vector(_) | KType::Set(_) => Shape::Array,
+            KType::Map(_,_) => Shape::Object(None),
+            KType::Option(x) | KType::Boxed(x) => x.shape(defs),
+            KType::Int64(_,KInt64Encoding::String) => Shape::String,
+            KType::Int64(_,_) => Shape::Number,
+            KType::Prim(Bool) => Shape::Bool,
+            KType::Prim(x) if x.is_integer() || x.is_float() => Shape::Number,
+            KType::Prim(Char) | KType::Prim(String) => Shape::String,
+            KType::Prim(_) => Shape::Unknown,
+            KType::Def(name) | KType::App(name,_) => match defs.get(name.as_str()) {
+                Some(KItem::New(x)) => x.origin.shape(defs),
+                Some(KItem::Nominal(x)) => x.origin.shape(defs),
+                Some(KItem::Enum(x)) if x.is_integer() => Shape::Number,
+                Some(KItem::Enum(_)) => Shape::String,
+                Some(KItem::Prod(x)) => Shape::Object(Some(object_keys(&x.fields))),
+                Some(KItem::Sum(_)) => Shape::Object(None),
+                _ => Shape::Unknown,
+            },
+            KType::Unit | KType::Never | KType::Unknown => Shape::Unknown,
+        }
+    }
+}
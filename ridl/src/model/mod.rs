@@ -6,19 +6,23 @@
 //! All types are `K` prefixed which means "Schema".
 
 pub mod log;
+pub mod lint;
 pub mod span;
 mod attr;
 mod r#type;
 
 pub mod skip;
 pub mod rename;
+pub mod int64;
+pub mod generic;
+pub mod recursion;
 pub mod rest;
 
 use serde_derive::{Serialize, Deserialize};
 
 pub use span::{KSpan, KLineColumn};
-pub use attr::{KAttrs, KAttrREST};
-pub use r#type::{KType, KScalarType, KPrimType, KTypeName};
+pub use attr::{KAttrs, KAttrREST, KAttrSerde};
+pub use r#type::{KType, KPrimType, KInt64Encoding, KTypeName};
 
 #[derive(Serialize,Deserialize)]
 #[derive(Eq,PartialEq)]
@@ -38,21 +42,38 @@ pub struct KMod {
 pub enum KItem {
     Mod(KMod),
     New(KNewType),
+    Nominal(KNominalType),
     Enum(KEnumType),
     Sum(KSumType),
     Prod(KProdType),
     Func(KFuncType),
+    Service(KService),
 }
+impl KMod {
+    /// Collects all items except modules by their names.
+    /// There's no namespace. All items are in a single flat namespace.
+    pub fn collect_defs<'a>(&'a self, defs:&mut crate::prelude::Map<&'a str, &'a KItem>) {
+        for item in self.items.iter() {
+            match item {
+                KItem::Mod(x) => x.collect_defs(defs),
+                _ => { defs.insert(item.name(), item); },
+            }
+        }
+    }
+}
+
 impl KItem {
     pub fn span(&self) -> &KSpan {
         use KItem::*;
         match self {
             Mod(x) => &x.span,
             New(x) => &x.span,
+            Nominal(x) => &x.span,
             Enum(x) => &x.span,
             Sum(x) => &x.span,
             Prod(x) => &x.span,
             Func(x) => &x.span,
+            Service(x) => &x.span,
         }
     }
     pub fn name(&self) -> &str {
@@ -60,10 +81,12 @@ impl KItem {
         match self {
             Mod(x) => x.name.as_str(),
             New(x) => x.name.as_str(),
+            Nominal(x) => x.name.as_str(),
             Enum(x) => x.name.as_str(),
             Sum(x) => x.name.as_str(),
             Prod(x) => x.name.as_str(),
             Func(x) => x.name.as_str(),
+            Service(x) => x.name.as_str(),
         }
     }
 }
@@ -76,6 +99,32 @@ impl KItem {
 pub struct KNewType {
     pub span: KSpan,
     pub name: String,
+    /// Names of generic type parameters.
+    #[serde(default)]
+    #[serde(skip_serializing_if="Vec::is_empty")]
+    pub type_params: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if="is_default")]
+    pub attrs: KAttrs,
+    pub comment: String,
+    pub origin: KType,
+}
+
+/// Single-field tuple struct. (e.g. `struct UserId(String);`)
+/// Serialized transparently as its origin type like `serde` does,
+/// but unlike `KNewType`, this is a distinct type which cannot be mixed up with its origin.
+#[derive(Serialize,Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct KNominalType {
+    pub span: KSpan,
+    pub name: String,
+    /// Names of generic type parameters.
+    #[serde(default)]
+    #[serde(skip_serializing_if="Vec::is_empty")]
+    pub type_params: Vec<String>,
     #[serde(default)]
     #[serde(skip_serializing_if="is_default")]
     pub attrs: KAttrs,
@@ -94,8 +143,21 @@ pub struct KEnumType {
     pub span: KSpan,
     pub name: String,
     pub comment: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if="is_default")]
+    pub attrs: KAttrs,
+    /// Integer type of serialized cases. (`#[repr(u8)]` with `serde_repr`)
+    /// Cases are serialized as their names if this is `None`.
+    #[serde(default)]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub repr: Option<KPrimType>,
     pub cases: Vec<KEnumTypeCase>,
 }
+impl KEnumType {
+    pub fn is_integer(&self) -> bool {
+        self.repr.is_some()
+    }
+}
 #[derive(Serialize,Deserialize)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
@@ -105,8 +167,14 @@ pub struct KEnumTypeCase {
     pub span: KSpan,
     pub name: String,
     pub comment: String,
-    // /// Underlying value for this case.
-    // pub value: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if="is_default")]
+    pub attrs: KAttrs,
+    /// Discriminant of this case. (e.g. `Low = 1`)
+    /// Implicit discriminants are filled only for integer-represented enums.
+    #[serde(default)]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub value: Option<i64>,
 }
 
 #[derive(Serialize,Deserialize)]
@@ -117,6 +185,10 @@ pub struct KEnumTypeCase {
 pub struct KSumType {
     pub span: KSpan,
     pub name: String,
+    /// Names of generic type parameters.
+    #[serde(default)]
+    #[serde(skip_serializing_if="Vec::is_empty")]
+    pub type_params: Vec<String>,
     pub comment: String,
     pub serialization: KSumTypeSerializationForm,
     #[serde(default)]
@@ -132,15 +204,23 @@ pub enum KSumTypeSerializationForm {
     /// Default serialization form of Rust(serde), Swift(Codable) and Smithy.
     /// Discriminant is a metadata of its content.
     NameBased,
-    /// **NOT SUPPORTED FOR NOW**
-    /// =========================
     /// Default serialization form of TypeScript and GraphQL.
     /// Discriminant is embedded in content.
     /// Therefore, content must be a prod-type.
     /// In this case, name of discriminant property is required to eliminate ambiguity.
     /// - `discriminant`: name of discriminant property embedded in content.
-    ///     This can be set by putting `#[ridl::form(tag="")]` on a sum-type definition.
+    ///     This can be set by putting `#[serde(tag="...")]` on a sum-type definition.
     TypeBased { discriminant: String },
+    /// Adjacently tagged form of Rust(serde).
+    /// Discriminant and content are stored in two properties of an object.
+    /// - `tag`: name of discriminant property.
+    /// - `content`: name of content property.
+    ///     This can be set by putting `#[serde(tag="...", content="...")]` on a sum-type definition.
+    Adjacent { tag: String, content: String },
+    /// Untagged form of Rust(serde).
+    /// No discriminant. Decoder tries variants in order and picks first one that succeeds.
+    /// This can be set by putting `#[serde(untagged)]` on a sum-type definition.
+    Untagged,
 }
 impl Default for KSumTypeSerializationForm {
     fn default() -> KSumTypeSerializationForm { KSumTypeSerializationForm::NameBased }
@@ -160,7 +240,57 @@ pub struct KSumTypeVariant {
     /// Type of stored data in this sum-type variant.
     /// Name-based sum-types can define array/optional content.
     /// Type-based sum-types only can define explicit reference to other type.
+    /// This is used only for newtype variants, and `KType::Unit` for other forms.
     pub content: KType,
+    /// Form of stored data.
+    #[serde(default)]
+    #[serde(skip_serializing_if="KSumTypeVariantForm::is_newtype")]
+    pub form: KSumTypeVariantForm,
+}
+impl KSumTypeVariant {
+    /// Visits all type expressions of stored data.
+    pub fn for_each_type(&self, f:&mut impl FnMut(&KType)) {
+        use KSumTypeVariantForm::*;
+        match &self.form {
+            Newtype => f(&self.content),
+            Unit => (),
+            Tuple(xs) => for x in xs.iter() { f(x) },
+            Struct(xs) => for x in xs.iter() { f(&x.content) },
+        }
+    }
+    /// Visits all type expressions of stored data.
+    pub fn for_each_type_mut(&mut self, f:&mut impl FnMut(&mut KType)) {
+        use KSumTypeVariantForm::*;
+        match &mut self.form {
+            Newtype => f(&mut self.content),
+            Unit => (),
+            Tuple(xs) => for x in xs.iter_mut() { f(x) },
+            Struct(xs) => for x in xs.iter_mut() { f(&mut x.content) },
+        }
+    }
+}
+/// Forms of sum-type variants.
+/// Each form is serialized as `serde` does.
+#[derive(Serialize,Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Clone)]
+#[derive(Default)]
+#[derive(Debug)]
+pub enum KSumTypeVariantForm {
+    /// `A(T)`. Stores `content`.
+    #[default]
+    Newtype,
+    /// `A`. Stores nothing.
+    Unit,
+    /// `A(T1, T2)`. Stored as an array.
+    Tuple(Vec<KType>),
+    /// `A { x: T }`. Stored as an object.
+    Struct(Vec<KProdTypeField>),
+}
+impl KSumTypeVariantForm {
+    pub fn is_newtype(&self) -> bool {
+        *self == KSumTypeVariantForm::Newtype
+    }
 }
 
 #[derive(Serialize,Deserialize)]
@@ -171,6 +301,10 @@ pub struct KSumTypeVariant {
 pub struct KProdType {
     pub span: KSpan,
     pub name: String,
+    /// Names of generic type parameters.
+    #[serde(default)]
+    #[serde(skip_serializing_if="Vec::is_empty")]
+    pub type_params: Vec<String>,
     pub comment: String,
     #[serde(default)]
     #[serde(skip_serializing_if="is_default")]
@@ -191,6 +325,12 @@ pub struct KProdTypeField {
     pub attrs: KAttrs,
     pub content: KType,
 }
+impl KProdTypeField {
+    /// Whether this field can be missing in serialized form.
+    pub fn is_omittable(&self) -> bool {
+        self.content.is_option() || self.attrs.has_serde_default() || self.attrs.has_serde_skip_serializing_if()
+    }
+}
 
 
 
@@ -209,7 +349,39 @@ pub struct KFuncType {
     pub input: KType,
     pub output: KType,
 }
-    
+
+/// Set of remote operations. (`trait`)
+#[derive(Serialize,Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct KService {
+    pub span: KSpan,
+    pub name: String,
+    pub comment: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if="is_default")]
+    pub attrs: KAttrs,
+    pub operations: Vec<KOperation>,
+}
+/// A method of service trait.
+/// Input is `KType::Unit` for a method without argument, and so is output for a method without return type.
+#[derive(Serialize,Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct KOperation {
+    pub span: KSpan,
+    pub name: String,
+    pub comment: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if="is_default")]
+    pub attrs: KAttrs,
+    pub input: KType,
+    pub output: KType,
+}
 
 
 
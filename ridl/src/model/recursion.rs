@@ -0,0 +1,180 @@
+//! Reference graph analysis over definitions.
+//!
+//! Value-type languages (e.g. Swift) cannot store a type in itself directly.
+//! Recursion cycles need to be found to put indirection somewhere in them.
+
+use crate::prelude::*;
+use super::*;
+
+/// How a type expression stores a referenced definition.
+/// Ordered from strongest to weakest.
+#[derive(Eq,PartialEq)]
+#[derive(Ord,PartialOrd)]
+#[derive(Clone,Copy)]
+#[derive(Debug)]
+pub enum KStorage {
+    /// Stored inline.
+    Direct,
+    /// Stored inline, but can be missing.
+    Optional,
+    /// Stored through a box.
+    Boxed,
+    /// Stored in a heap-allocated container. (e.g. `Vec`)
+    Heap,
+}
+
+impl KType {
+    /// Visits all names to definitions in this type expression with their weakest storage.
+    pub fn for_each_ref(&self, f:&mut impl FnMut(&str, KStorage)) {
+        self.for_each_ref_in(KStorage::Direct, f)
+    }
+    fn for_each_ref_in(&self, storage:KStorage, f:&mut impl FnMut(&str, KStorage)) {
+        use KType::*;
+        match self {
+            Vector(x) | Set(x) => x.for_each_ref_in(KStorage::Heap, f),
+            Map(k,v) => {
+                k.for_each_ref_in(KStorage::Heap, f);
+                v.for_each_ref_in(KStorage::Heap, f);
+            },
+            Option(x) => x.for_each_ref_in(storage.max(KStorage::Optional), f),
+            Boxed(x) => x.for_each_ref_in(storage.max(KStorage::Boxed), f),
+            Def(x) => f(x, storage),
+            App(x,args) => {
+                f(x, storage);
+                // Storage of arguments depends on generic definition. Assumes the weakest.
+                for arg in args.iter() { arg.for_each_ref_in(KStorage::Heap, f) }
+            },
+            Int64(_,_) | Prim(_) | Unit | Never | Unknown => (),
+        }
+    }
+    /// Whether this type expression references any of `names` with storage accepted by `is_inline`.
+    pub fn refers_inline(&self, names:&[&str], is_inline:&impl Fn(KStorage) -> bool) -> bool {
+        let mut found = false;
+        self.for_each_ref(&mut |name,storage| found |= is_inline(storage) && names.contains(&name));
+        found
+    }
+}
+
+impl KItem {
+    /// Visits type expressions which make up storage of this definition.
+    fn for_each_member_type(&self, f:&mut impl FnMut(&KType)) {
+        use KItem::*;
+        match self {
+            New(x) => f(&x.origin),
+            Nominal(x) => f(&x.origin),
+            Sum(x) => for x in x.variants.iter() { x.for_each_type(f) },
+            Prod(x) => for x in x.fields.iter() { f(&x.content) },
+            Mod(_) | Enum(_) | Func(_) | Service(_) => (),
+        }
+    }
+}
+
+impl KMod {
+    /// Finds groups of definitions which reference each other through storages accepted by `is_inline`.
+    /// Each group is a strongly connected component with at least one cycle.
+    pub fn find_cycles(&self, is_inline:impl Fn(KStorage) -> bool) -> Vec<Vec<&str>> {
+        self.find_cycles_among(|_| true, is_inline)
+    }
+    /// Finds groups of type aliases which reference each other.
+    /// Aliases are expanded in place, so any storage makes a cycle.
+    pub fn find_alias_cycles(&self) -> Vec<Vec<&str>> {
+        self.find_cycles_among(|x| matches!(x, KItem::New(_)), |_| true)
+    }
+    fn find_cycles_among(&self, is_node:impl Fn(&KItem) -> bool, is_inline:impl Fn(KStorage) -> bool) -> Vec<Vec<&str>> {
+        let mut defs = Map::new();
+        self.collect_defs(&mut defs);
+        defs.retain(|_,x| is_node(x));
+        let names = defs.keys().copied().collect::<Vec<_>>();
+        let mut edges = vec![Vec::new(); names.len()];
+        for (i,name) in names.iter().enumerate() {
+            defs[name].for_each_member_type(&mut |x| x.for_each_ref(&mut |to,storage| {
+                if !is_inline(storage) { return }
+                if let Some(j) = names.iter().position(|x| *x == to) { edges[i].push(j) }
+            }));
+        }
+        let mut cycles = Vec::new();
+        for scc in strongly_connected_components(&edges) {
+            let is_cycle = scc.len() > 1 || edges[scc[0]].contains(&scc[0]);
+            if is_cycle { cycles.push(scc.iter().map(|i| names[*i]).collect()) }
+        }
+        cycles
+    }
+}
+
+/// Tarjan's algorithm.
+/// Components are ordered by their first node, and nodes in a component are sorted.
+fn strongly_connected_components(edges:&[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct State<'a> {
+        edges: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next: usize,
+        sccs: Vec<Vec<usize>>,
+    }
+    fn visit(s:&mut State, v:usize) {
+        s.index[v] = Some(s.next);
+        s.lowlink[v] = s.next;
+        s.next += 1;
+        s.stack.push(v);
+        s.on_stack[v] = true;
+        for &w in s.edges[v].iter() {
+            match s.index[w] {
+                None => {
+                    visit(s, w);
+                    s.lowlink[v] = s.lowlink[v].min(s.lowlink[w]);
+                },
+                Some(i) if s.on_stack[w] => s.lowlink[v] = s.lowlink[v].min(i),
+                Some(_) => (),
+            }
+        }
+        if Some(s.lowlink[v]) == s.index[v] {
+            let mut scc = Vec::new();
+            while let Some(w) = s.stack.pop() {
+                s.on_stack[w] = false;
+                scc.push(w);
+                if w == v { break }
+            }
+            scc.sort();
+            s.sccs.push(scc);
+        }
+    }
+    let n = edges.len();
+    let mut s = State { edges, index: vec![None; n], lowlink: vec![0; n], on_stack: vec![false; n], stack: Vec::new(), next: 0, sccs: Vec::new() };
+    for v in 0..n {
+        if s.index[v].is_none() { visit(&mut s, v) }
+    }
+    let mut sccs = s.sccs;
+    sccs.sort();
+    sccs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_cycles() {
+        let def = |x:&str| KType::Def(x.to_string());
+        let prod = |name:&str, x:KType| KItem::Prod(KProdType {
+            name: name.to_string(),
+            fields: vec![KProdTypeField { name: "x".to_string(), content: x, ..Default::default() }],
+            ..Default::default()
+        });
+        let m = KMod {
+            span: KSpan::default(),
+            name: String::new(),
+            comment: String::new(),
+            items: vec![
+                prod("A", def("B")),
+                prod("B", KType::Option(Box::new(def("A")))),
+                prod("C", KType::Vector(Box::new(def("C")))),
+                prod("D", def("A")),
+            ],
+        };
+        assert_eq!(m.find_cycles(|x| x == KStorage::Direct), Vec::<Vec<&str>>::new());
+        assert_eq!(m.find_cycles(|x| x != KStorage::Heap), vec![vec!["A", "B"]]);
+        assert_eq!(m.find_cycles(|_| true), vec![vec!["A", "B"], vec!["C"]]);
+    }
+}
@@ -1,5 +1,7 @@
+use serde_derive::{Serialize, Deserialize};
 use super::*;
 
+/// Fallback rules for items without `#[serde(rename_all="...")]`.
 #[derive(Default)]
 #[derive(Clone, Copy)]
 pub struct Options {
@@ -8,19 +10,128 @@ pub struct Options {
     pub field: Option<Rule>,
 }
 /// Follows [`serde` renaming rules](https://serde.rs/container-attrs.html#rename_all).
+/// Accepts both of `serde` rule names and short names for command line.
 #[derive(strum_macros::EnumString)]
+#[derive(Serialize,Deserialize)]
+#[derive(Eq,PartialEq)]
 #[derive(Clone, Copy)]
+#[derive(Debug)]
 pub enum Rule {
-    #[strum(serialize="camel")]
+    #[strum(serialize="lowercase", serialize="lower")]
+    #[serde(rename="lowercase")]
+    LowerCase,
+    #[strum(serialize="UPPERCASE", serialize="upper")]
+    #[serde(rename="UPPERCASE")]
+    UpperCase,
+    #[strum(serialize="PascalCase", serialize="pascal")]
+    #[serde(rename="PascalCase")]
+    PascalCase,
+    #[strum(serialize="camelCase", serialize="camel")]
+    #[serde(rename="camelCase")]
     CamelCase,
+    #[strum(serialize="snake_case", serialize="snake")]
+    #[serde(rename="snake_case")]
+    SnakeCase,
+    #[strum(serialize="SCREAMING_SNAKE_CASE", serialize="screaming-snake")]
+    #[serde(rename="SCREAMING_SNAKE_CASE")]
+    ScreamingSnakeCase,
+    #[strum(serialize="kebab-case", serialize="kebab")]
+    #[serde(rename="kebab-case")]
+    KebabCase,
+    #[strum(serialize="SCREAMING-KEBAB-CASE", serialize="screaming-kebab")]
+    #[serde(rename="SCREAMING-KEBAB-CASE")]
+    ScreamingKebabCase,
+}
+impl Rule {
+    /// Name of this rule in `#[serde(rename_all="...")]`.
+    pub fn serde_name(&self) -> &'static str {
+        use Rule::*;
+        match self {
+            LowerCase => "lowercase",
+            UpperCase => "UPPERCASE",
+            PascalCase => "PascalCase",
+            CamelCase => "camelCase",
+            SnakeCase => "snake_case",
+            ScreamingSnakeCase => "SCREAMING_SNAKE_CASE",
+            KebabCase => "kebab-case",
+            ScreamingKebabCase => "SCREAMING-KEBAB-CASE",
+        }
+    }
+    /// Renames a `PascalCase` Rust variant name.
+    pub fn apply_to_variant(&self, n:&str) -> String {
+        use Rule::*;
+        match self {
+            PascalCase => n.to_string(),
+            LowerCase => n.to_ascii_lowercase(),
+            UpperCase => n.to_ascii_uppercase(),
+            CamelCase => pascal_case_to_camel_case(n),
+            SnakeCase => pascal_case_to_snake_case(n),
+            ScreamingSnakeCase => pascal_case_to_snake_case(n).to_ascii_uppercase(),
+            KebabCase => pascal_case_to_snake_case(n).replace('_', "-"),
+            ScreamingKebabCase => pascal_case_to_snake_case(n).to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+    /// Renames a `snake_case` Rust field name.
+    pub fn apply_to_field(&self, n:&str) -> String {
+        use Rule::*;
+        match self {
+            LowerCase | SnakeCase => n.to_string(),
+            UpperCase | ScreamingSnakeCase => n.to_ascii_uppercase(),
+            PascalCase => first_char_capitalized(&snake_to_camel_case(n)),
+            CamelCase => snake_to_camel_case(n),
+            KebabCase => n.replace('_', "-"),
+            ScreamingKebabCase => n.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
 }
 
 impl KMod {
+    /// Renames enum-type cases, sum-type variants and prod-type fields to their serialized names.
+    /// - `#[serde(rename="...")]` on a member takes priority.
+    /// - `#[serde(rename_all="...")]` on a container comes next.
+    /// - Rules in `options` are applied to everything else.
+    ///
+    /// Consumed `serde` attributes will be removed to prevent double application.
+    /// REST path placeholders are renamed to serialized names of their `#[path]` fields.
     pub fn rename(&mut self, options:&Options) {
+        let original = self.clone();
+        let mut defs = crate::prelude::Map::new();
+        original.collect_defs(&mut defs);
+        self.rename_path_placeholders(&defs, options);
         for item in self.items.iter_mut() {
             item.rename(options);
         }
     }
+    fn rename_path_placeholders(&mut self, defs:&crate::prelude::Map<&str, &KItem>, options:&Options) {
+        for item in self.items.iter_mut() {
+            match item {
+                KItem::Mod(x) => x.rename_path_placeholders(defs, options),
+                KItem::Func(x) => rename_path_placeholders(&mut x.attrs, &x.input, defs, options),
+                KItem::Service(x) => for op in x.operations.iter_mut() { rename_path_placeholders(&mut op.attrs, &op.input, defs, options) },
+                _ => (),
+            }
+        }
+    }
+}
+
+fn rename_path_placeholders(attrs:&mut KAttrs, input:&KType, defs:&crate::prelude::Map<&str, &KItem>, options:&Options) {
+    let message = match input {
+        KType::Def(name) => match defs.get(name.as_str()) {
+            Some(KItem::Prod(x)) => x,
+            _ => return,
+        },
+        _ => return,
+    };
+    let names = message.renamed_field_names(options);
+    for x in attrs.rest.iter_mut() {
+        if let KAttrREST::FnPath(path) = x {
+            let renamed = rest::replace_placeholders(path, |p| Ok::<_,()>(match message.path_field_index(p) {
+                Some(i) => format!("{{{}}}", names[i]),
+                None => format!("{{{}}}", p),
+            }));
+            if let Ok(renamed) = renamed { *path = renamed }
+        }
+    }
 }
 
 impl KItem {
@@ -38,55 +149,73 @@ impl KItem {
 
 impl KEnumType {
     fn rename(&mut self, options:&Options) {
+        let rule = self.attrs.serde_rename_all().or(options.case);
         for case in self.cases.iter_mut() {
-            case.rename(options);
-        }
-    }
-}
-
-impl KEnumTypeCase {
-    fn rename(&mut self, options:&Options) {
-        if let Some(rule) = &options.case {
-            match rule {
-            Rule::CamelCase => self.name = pascal_case_to_camel_case(&self.name),
-            }
+            case.name = rename_variant(&case.name, &case.attrs, rule);
+            case.attrs.remove_serde_renames();
         }
+        self.attrs.remove_serde_renames();
     }
 }
 
 impl KSumType {
     fn rename(&mut self, options:&Options) {
+        let rule = self.attrs.serde_rename_all().or(options.variant);
         for variant in self.variants.iter_mut() {
-            variant.rename(options);
+            variant.name = rename_variant(&variant.name, &variant.attrs, rule);
+            // `rename_all` on a struct variant applies to its fields.
+            let field_rule = variant.attrs.serde_rename_all().or(options.field);
+            if let KSumTypeVariantForm::Struct(fields) = &mut variant.form { rename_fields(fields, field_rule) }
+            variant.attrs.remove_serde_renames();
         }
+        self.attrs.remove_serde_renames();
     }
 }
 
-impl KSumTypeVariant {
+impl KProdType {
     fn rename(&mut self, options:&Options) {
-        if let Some(rule) = &options.variant {
-            match rule {
-            Rule::CamelCase => self.name = pascal_case_to_camel_case(&self.name),
-            }
-        }
+        let rule = self.attrs.serde_rename_all().or(options.field);
+        rename_fields(&mut self.fields, rule);
+        self.attrs.remove_serde_renames();
     }
 }
 
 impl KProdType {
-    fn rename(&mut self, options:&Options) {
-        for field in self.fields.iter_mut() {
-            field.rename(options);
-        }
+    /// Serialized names of fields by `serde` attributes only.
+    /// For Rust code which keeps Rust names but must know names on wire.
+    pub fn serde_field_names(&self) -> Vec<String> {
+        self.renamed_field_names(&Options::default())
+    }
+    /// Names of fields after renaming with `options`.
+    fn renamed_field_names(&self, options:&Options) -> Vec<String> {
+        let mut fields = self.fields.clone();
+        rename_fields(&mut fields, self.attrs.serde_rename_all().or(options.field));
+        fields.into_iter().map(|x| x.name).collect()
     }
 }
 
-impl KProdTypeField {
-    fn rename(&mut self, options:&Options) {
-        if let Some(rule) = &options.field {
-            match rule {
-            Rule::CamelCase => self.name = snake_to_camel_case(&self.name),
-            }
-        }
+fn rename_fields(fields:&mut [KProdTypeField], rule:Option<Rule>) {
+    for field in fields.iter_mut() {
+        field.name = match (field.attrs.serde_rename(), rule) {
+            (Some(x), _) => x.to_string(),
+            (None, Some(rule)) => rule.apply_to_field(&field.name),
+            (None, None) => field.name.clone(),
+        };
+        field.attrs.remove_serde_renames();
+    }
+}
+
+fn rename_variant(name:&str, attrs:&KAttrs, rule:Option<Rule>) -> String {
+    match (attrs.serde_rename(), rule) {
+        (Some(x), _) => x.to_string(),
+        (None, Some(rule)) => rule.apply_to_variant(name),
+        (None, None) => name.to_string(),
+    }
+}
+
+impl KAttrs {
+    fn remove_serde_renames(&mut self) {
+        self.serde.retain(|x| !matches!(x, KAttrSerde::Rename(_) | KAttrSerde::RenameAll(_)));
     }
 }
 
@@ -98,6 +227,14 @@ fn pascal_case_to_camel_case(n:&str) -> String {
     s.extend(chs);
     s
 }
+fn pascal_case_to_snake_case(n:&str) -> String {
+    let mut s = String::new();
+    for (i,ch) in n.char_indices() {
+        if i > 0 && ch.is_uppercase() { s.push('_') }
+        s.extend(ch.to_lowercase());
+    }
+    s
+}
 fn snake_to_camel_case(n:&str) -> String {
     let mut s = String::new();
     let mut comps = n.split("_");
@@ -114,4 +251,22 @@ fn first_char_capitalized(s:&str) -> String {
     for ch in a.to_uppercase() { z.push(ch) }
     for ch in chs { z.push(ch) }
     z
-}
\ No newline at end of file
+}
+
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply_rules() {
+        assert_eq!(Rule::CamelCase.apply_to_variant("IronMetal"), "ironMetal");
+        assert_eq!(Rule::SnakeCase.apply_to_variant("IronMetal"), "iron_metal");
+        assert_eq!(Rule::ScreamingKebabCase.apply_to_variant("IronMetal"), "IRON-METAL");
+        assert_eq!(Rule::CamelCase.apply_to_field("living_address"), "livingAddress");
+        assert_eq!(Rule::PascalCase.apply_to_field("living_address"), "LivingAddress");
+        assert_eq!(Rule::KebabCase.apply_to_field("living_address"), "living-address");
+    }
+}
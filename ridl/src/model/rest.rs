@@ -1,3 +1,242 @@
+//! REST endpoint declarations. (`#[rest(METHOD, "/path")]`)
+
+use crate::prelude::*;
+use super::*;
+use super::log::{self, Log, ErrorLogs};
+
+/// HTTP methods which can be used in `#[rest(METHOD, "/path")]`.
+pub const HTTP_METHODS: &[&str] = &["GET", "PUT", "POST", "DELETE", "OPTIONS", "HEAD", "PATCH", "TRACE"];
+
+/// Parses a path template like `/orders/{id}`.
+/// Returns names of placeholders in order, or a reason of bad syntax.
+pub fn path_placeholders(path:&str) -> Result<Vec<&str>,String> {
+    if !path.starts_with('/') { return Err("path must start with `/`".to_string()) }
+    let mut names = Vec::new();
+    let mut rest = path;
+    while let Some(i) = rest.find(|c| c == '{' || c == '}') {
+        if rest[i..].starts_with('}') { return Err("unmatched `}` in path".to_string()) }
+        let end = match rest[i+1..].find(|c| c == '{' || c == '}' || c == '/') {
+            Some(j) if rest[i+1+j..].starts_with('}') => i+1+j,
+            _ => return Err("unclosed `{` in path".to_string()),
+        };
+        let name = &rest[i+1..end];
+        let is_ident = !name.is_empty() && name.chars().enumerate().all(|(k,c)| c == '_' || c.is_ascii_alphabetic() || (k > 0 && c.is_ascii_digit()));
+        if !is_ident { return Err(format!("bad path placeholder `{{{}}}`", name)) }
+        if names.contains(&name) { return Err(format!("duplicate path placeholder `{{{}}}`", name)) }
+        names.push(name);
+        rest = &rest[end+1..];
+    }
+    Ok(names)
+}
+
+/// Replaces each placeholder in a path template with `f(name)`.
+/// Other parts are kept as is.
+pub fn replace_placeholders<E>(path:&str, mut f:impl FnMut(&str) -> Result<String,E>) -> Result<String,E> {
+    let mut x = String::new();
+    let mut rest = path;
+    while let (Some(i), Some(j)) = (rest.find('{'), rest.find('}')) {
+        x.push_str(&rest[..i]);
+        x.push_str(&f(&rest[i+1..j])?);
+        rest = &rest[j+1..];
+    }
+    x.push_str(rest);
+    Ok(x)
+}
+
+/// HTTP endpoint declared by a function-type alias or a service trait method.
+pub struct KEndpoint<'a> {
+    pub span: KSpan,
+    pub name: &'a str,
+    pub comment: &'a str,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub input: &'a KType,
+    pub output: &'a KType,
+    /// Name of owning service.
+    pub service: Option<&'a str>,
+    pub attrs: &'a KAttrs,
+}
+
+impl KMod {
+    /// All items with REST method and path in declaration order.
+    /// Items without them are not HTTP endpoints and skipped.
+    pub fn endpoints(&self) -> Vec<KEndpoint<'_>> {
+        let mut xs = Vec::new();
+        self.collect_endpoints(&mut xs);
+        xs
+    }
+    fn collect_endpoints<'a>(&'a self, xs:&mut Vec<KEndpoint<'a>>) {
+        let make = |span:KSpan, name:&'a str, comment:&'a str, attrs:&'a KAttrs, input:&'a KType, output:&'a KType, service:Option<&'a str>| {
+            let method = attrs.rest_method()?;
+            let path = attrs.rest_path()?;
+            Some(KEndpoint { span: span, name: name, comment: comment, method: method, path: path, input: input, output: output, service: service, attrs: attrs })
+        };
+        for item in self.items.iter() {
+            match item {
+                KItem::Mod(x) => x.collect_endpoints(xs),
+                KItem::Func(x) => xs.extend(make(x.span, &x.name, &x.comment, &x.attrs, &x.input, &x.output, None)),
+                KItem::Service(x) => for op in x.operations.iter() {
+                    xs.extend(make(op.span, &op.name, &op.comment, &op.attrs, &op.input, &op.output, Some(&x.name)));
+                },
+                _ => (),
+            }
+        }
+    }
+}
+
+impl KMod {
+    /// Endpoints with names converted by `convert` for a target language.
+    /// Converted names must be unique, and cannot be one of `reserved`.
+    pub fn named_endpoints(&self, convert:impl Fn(&str) -> String, reserved:&[&str]) -> log::Result<Vec<(String, KEndpoint<'_>)>> {
+        let mut xs = Vec::<(String, KEndpoint)>::new();
+        for x in self.endpoints() {
+            let name = convert(x.name);
+            if reserved.contains(&name.as_str()) || xs.iter().any(|(y,_)| *y == name) {
+                return err(x.span, &format!("duplicate endpoint name `{}`", name))
+            }
+            xs.push((name, x));
+        }
+        Ok(xs)
+    }
+}
+
+impl<'a> KEndpoint<'a> {
+    /// Input if it is a `#[rest(in)]` message.
+    pub fn message_in(&self, defs:&Map<&str,&'a KItem>) -> Option<&'a KProdType> {
+        match self.input {
+            KType::Def(name) => match defs.get(name.as_str()) {
+                Some(KItem::Prod(x)) if x.attrs.rest.contains(&KAttrREST::MessageIn) => Some(x),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    /// Output if it is a `#[rest(out)]` message.
+    pub fn message_out(&self, defs:&Map<&str,&'a KItem>) -> Option<&'a KSumType> {
+        match self.output {
+            KType::Def(name) => match defs.get(name.as_str()) {
+                Some(KItem::Sum(x)) if x.attrs.rest.contains(&KAttrREST::MessageOut) => Some(x),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Where a field of `#[rest(in)]` message goes in HTTP request.
+#[derive(Eq,PartialEq)]
+#[derive(Clone,Copy)]
+#[derive(Debug)]
+pub enum KParamLocation<'a> {
+    Path,
+    Query,
+    /// With wire name.
+    Header(&'a str),
+    /// With wire name.
+    Cookie(&'a str),
+    /// With MIME-Type.
+    Body(&'a str),
+}
+
+impl KProdTypeField {
+    /// `None` if the field has no location, or is a body without MIME-Type.
+    pub fn rest_location(&self) -> Option<KParamLocation<'_>> {
+        self.attrs.rest.iter().find_map(|x| match x {
+            KAttrREST::PathParam => Some(KParamLocation::Path),
+            KAttrREST::QueryParam => Some(KParamLocation::Query),
+            KAttrREST::HeaderParam(name) => Some(KParamLocation::Header(name.as_deref().unwrap_or(&self.name))),
+            KAttrREST::CookieParam(name) => Some(KParamLocation::Cookie(name.as_deref().unwrap_or(&self.name))),
+            KAttrREST::BodyParam => Some(KParamLocation::Body(self.attrs.rest_mime()?)),
+            _ => None,
+        })
+    }
+}
+
+impl KProdType {
+    /// Fields of `#[rest(in)]` message with their locations in declaration order.
+    /// Scanner has checked that every field has a location.
+    pub fn rest_params(&self) -> impl Iterator<Item=(&KProdTypeField, KParamLocation<'_>)> {
+        self.fields.iter().filter_map(|x| Some((x, x.rest_location()?)))
+    }
+    /// `#[body]` fields with their MIME-Types.
+    /// Multiple bodies are optional alternatives, and the first present one is sent.
+    pub fn rest_bodies(&self) -> Vec<(&KProdTypeField, &str)> {
+        self.rest_params().filter_map(|(x,location)| if let KParamLocation::Body(mime) = location { Some((x, mime)) } else { None }).collect()
+    }
+    /// `#[path]` field for a path placeholder.
+    /// Placeholder matches a field by its Rust name or serialized name.
+    pub fn path_field(&self, placeholder:&str) -> Option<&KProdTypeField> {
+        self.path_field_index(placeholder).map(|i| &self.fields[i])
+    }
+    /// Index of `#[path]` field for a path placeholder.
+    pub fn path_field_index(&self, placeholder:&str) -> Option<usize> {
+        let serde_names = self.serde_field_names();
+        (0..self.fields.len()).find(|&i| {
+            let x = &self.fields[i];
+            x.rest_location() == Some(KParamLocation::Path) && (x.name == placeholder || serde_names[i] == placeholder)
+        })
+    }
+}
+
+/// A case to pick a variant of `#[rest(out)]` message for an HTTP response.
+pub struct KResponseCase<'a> {
+    /// `None` for `#[status(default)]` which matches any status.
+    pub status: Option<i64>,
+    /// MIME-Type which `Content-Type` header must start with.
+    /// `None` matches any content.
+    pub content_type: Option<&'a str>,
+    /// MIME-Type of payload to decode.
+    /// `None` for unit variants.
+    pub mime: Option<&'a str>,
+    pub variant: &'a KSumTypeVariant,
+}
+
+impl KSumType {
+    /// Cases to try in order for a response.
+    /// Variants are grouped by status, and `#[status(default)]` variants come last.
+    /// In a status, variants with payload are picked by `Content-Type`, and unit variants after them match any content.
+    /// Cases after one matching any content are unreachable and dropped.
+    /// Also returns whether the cases cover every response.
+    pub fn rest_response_cases(&self) -> log::Result<(Vec<KResponseCase<'_>>, bool)> {
+        let mut groups = Vec::<(Option<i64>, Vec<&KSumTypeVariant>)>::new();
+        for v in self.variants.iter() {
+            let status = match (v.attrs.rest_status(), v.attrs.rest.contains(&KAttrREST::DefaultStatus)) {
+                (_, true) => None,
+                (Some(x), false) => Some(x),
+                (None, false) => return err(v.span, "`#[rest(out)]` variant needs `#[status(...)]` attribute"),
+            };
+            match groups.iter_mut().find(|(x,_)| *x == status) {
+                Some((_,vs)) => vs.push(v),
+                None => groups.push((status, vec![v])),
+            }
+        }
+        groups.sort_by_key(|(x,_)| x.is_none());
+        let mut cases = Vec::new();
+        for (status,mut vs) in groups {
+            vs.sort_by_key(|x| x.form == KSumTypeVariantForm::Unit);
+            let is_ambiguous = vs.len() > 1;
+            for v in vs {
+                let mime = match (&v.form, v.attrs.rest_mime()) {
+                    (KSumTypeVariantForm::Unit, _) => None,
+                    (KSumTypeVariantForm::Newtype, Some(x)) => Some(x),
+                    (KSumTypeVariantForm::Newtype, None) => return err(v.span, "`#[rest(out)]` variant with payload needs `#[mime(\"...\")]` attribute"),
+                    _ => return err(v.span, "`#[rest(out)]` variant must be a unit or a new-type to be decoded"),
+                };
+                let content_type = if is_ambiguous { mime } else { None };
+                cases.push(KResponseCase { status: status, content_type: content_type, mime: mime, variant: v });
+                if content_type.is_none() { break }
+            }
+        }
+        let is_exhaustive = matches!(cases.last(), Some(x) if x.status.is_none() && x.content_type.is_none());
+        Ok((cases, is_exhaustive))
+    }
+}
+
+fn err<T>(span:KSpan, message:&str) -> log::Result<T> {
+    let log = Log { span: span, message: PString::new(message.to_string()) };
+    Err(ErrorLogs(PVec::from(vec![log])))
+}
+
 // use serde::{Serialize,Deserialize};
 
 // /// Data structure to support REST-like API.
@@ -56,4 +295,29 @@
 // pub type RESTMethod = String;
 // pub type RESTParametricPath = String;
 // /// Put a name to a defined type name in RIDL schema.
-// pub type RESTMessageTypeName = String;
\ No newline at end of file
+// pub type RESTMessageTypeName = String;
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_path_placeholders() {
+        assert_eq!(path_placeholders("/orders"), Ok(vec![]));
+        assert_eq!(path_placeholders("/orders/{id}/items/{item_id}"), Ok(vec!["id", "item_id"]));
+        assert!(path_placeholders("orders").is_err());
+        assert!(path_placeholders("/orders/{id").is_err());
+        assert!(path_placeholders("/orders/{id/x}").is_err());
+        assert!(path_placeholders("/orders/id}").is_err());
+        assert!(path_placeholders("/orders/{}").is_err());
+        assert!(path_placeholders("/orders/{id}/{id}").is_err());
+    }
+
+    #[test]
+    fn replace_path_placeholders() {
+        let x = replace_placeholders("/orders/{id}/items/{item_id}", |x| Ok::<_,()>(x.to_uppercase()));
+        assert_eq!(x, Ok("/orders/ID/items/ITEM_ID".to_string()));
+    }
+}
@@ -13,6 +13,13 @@ impl KMod {
             item.retain_only_non_skipping_items_in_set(skippings);
         }
     }
+    /// Removes all enum-type cases, sum-type variants and prod-type fields marked with `#[serde(skip)]`.
+    /// They never appear in serialized form.
+    pub fn retain_only_non_skipped_members(&mut self) {
+        for item in self.items.iter_mut() {
+            item.retain_only_non_skipped_members();
+        }
+    }
 }
 impl KItem {
     fn retain_only_non_skipping_items_in_set(&mut self, skippings: &HashSet<&str>) {
@@ -21,4 +28,18 @@ impl KItem {
             _ => (),
         }
     }
+    fn retain_only_non_skipped_members(&mut self) {
+        match self {
+            KItem::Mod(x) => x.retain_only_non_skipped_members(),
+            KItem::Enum(x) => x.cases.retain(|x| !x.attrs.has_serde_skip()),
+            KItem::Sum(x) => {
+                x.variants.retain(|x| !x.attrs.has_serde_skip());
+                for v in x.variants.iter_mut() {
+                    if let KSumTypeVariantForm::Struct(fields) = &mut v.form { fields.retain(|x| !x.attrs.has_serde_skip()) }
+                }
+            },
+            KItem::Prod(x) => x.fields.retain(|x| !x.attrs.has_serde_skip()),
+            _ => (),
+        }
+    }
 }
@@ -1,19 +1,46 @@
 use serde_derive::{Serialize, Deserialize};
 
-/// An invented concept to simplify type definition.
-/// Proper support for optional/array types will require full support for generics.
-/// To eliminate complexity of generics support, I just baked-in some essential generic patterns.
+/// A type expression.
+/// Container types can be nested to any depth. (e.g. `Option<Vec<Vec<f64>>>`)
 #[derive(Serialize,Deserialize)]
 #[derive(Eq,PartialEq)]
 #[derive(Clone)]
 #[derive(Debug)]
+#[serde(from="KTypeRepr")]
 pub enum KType {
     /// 0..N homogeneous instances.
-    Vector(KScalarType),
+    Vector(Box<KType>),
     /// 0..1 instances.
-    Option(KScalarType),
-    /// Always 1 instance.
-    Scalar(KScalarType),
+    Option(Box<KType>),
+    /// 0..N values keyed by unique keys.
+    /// Serialized as an object. Therefore, keys are always serialized as strings.
+    /// Key must be a string, an integer or an enum-type.
+    Map(Box<KType>, Box<KType>),
+    /// 0..N unique homogeneous instances.
+    /// Serialized as an array.
+    Set(Box<KType>),
+    /// Reference through a heap allocation. (`Box`, `Rc`, `Arc`)
+    /// Serialized transparently as its content.
+    /// Recorded to let renderers break recursive definitions.
+    Boxed(Box<KType>),
+    /// Name to a defined type.
+    /// This must be a defined name in schema document,
+    /// or a type parameter of enclosing generic definition.
+    Def(KTypeName),
+    /// Application of type arguments to a generic type definition.
+    /// e.g. `Page<Order>`.
+    App(KTypeName, Vec<KType>),
+    /// 64-bit integer with an explicit wire encoding.
+    /// Scanner never makes this. `KMod::encode_int64` replaces 64-bit `Prim`s with this.
+    Int64(KPrimType, KInt64Encoding),
+    /// Pre-defined primitive types.
+    /// Some code-gen can reject certain set of primitive types.
+    /// Primitive types are implicitly defined by KCG.
+    Prim(KPrimType),
+    /// Unit type.
+    /// Some code-gen can reject unit type.
+    /// Unit type is implicitly defined by KCG.
+    Unit,
     /// Always 0 instance.
     Never,
     /// Unsupported types.
@@ -27,33 +54,76 @@ impl KType {
     pub fn is_option(&self) -> bool {
         if let KType::Option(_) = self { true } else { false }
     }
-    pub fn is_scalar(&self) -> bool {
-        if let KType::Scalar(_) = self { true } else { false }
+    pub fn is_map(&self) -> bool {
+        if let KType::Map(_,_) = self { true } else { false }
+    }
+    pub fn is_set(&self) -> bool {
+        if let KType::Set(_) = self { true } else { false }
+    }
+    /// Whether this is stored through a box.
+    /// Optional box also counts as `Option` stores its content inline.
+    pub fn is_boxed(&self) -> bool {
+        match self {
+            KType::Boxed(_) => true,
+            KType::Option(x) => x.is_boxed(),
+            _ => false,
+        }
+    }
+    /// Whether `f` is true for this type expression or any of its nested type expressions.
+    pub fn any(&self, f:&impl Fn(&KType) -> bool) -> bool {
+        use KType::*;
+        if f(self) { return true }
+        match self {
+            Vector(x) | Option(x) | Set(x) | Boxed(x) => x.any(f),
+            Map(k,v) => k.any(f) || v.any(f),
+            App(_,args) => args.iter().any(|x| x.any(f)),
+            Def(_) | Int64(_,_) | Prim(_) | Unit | Never | Unknown => false,
+        }
     }
 }
 impl Default for KType {
     fn default() -> KType { KType::Unknown }
 }
 
-#[derive(Serialize,Deserialize)]
-#[derive(Eq,PartialEq)]
-#[derive(Clone)]
-#[derive(Debug)]
-pub enum KScalarType {
-    /// Name to a defined type.
-    /// This must be a defined name in schema document.
+/// Deserialization form of `KType`.
+/// Also accepts older forms to keep old RIDL1 documents loadable.
+#[derive(Deserialize)]
+enum KTypeRepr {
+    Vector(Box<KType>),
+    Option(Box<KType>),
+    Map(Box<KType>, Box<KType>),
+    Set(Box<KType>),
+    Boxed(Box<KType>),
     Def(KTypeName),
-    /// Pre-defined primitive types.
-    /// Some code-gen can reject certain set of primitive types.
-    /// Primitive types are implicitly defined by KCG.
+    App(KTypeName, Vec<KType>),
+    Int64(KPrimType, KInt64Encoding),
     Prim(KPrimType),
-    /// Unit type.
-    /// Some code-gen can reject unit type.
-    /// Unit type is implicitly defined by KCG.
     Unit,
+    Never,
+    Unknown,
+    /// Older form. Always 1 instance of a non-container type.
+    /// Now non-container types are `KType`s themselves.
+    Scalar(Box<KType>),
 }
-impl Default for KScalarType {
-    fn default() -> KScalarType { Self::Unit }
+impl From<KTypeRepr> for KType {
+    fn from(x:KTypeRepr) -> KType {
+        use KTypeRepr::*;
+        match x {
+            Vector(x) => KType::Vector(x),
+            Option(x) => KType::Option(x),
+            Map(k,v) => KType::Map(k,v),
+            Set(x) => KType::Set(x),
+            Boxed(x) => KType::Boxed(x),
+            Def(x) => KType::Def(x),
+            App(x,args) => KType::App(x,args),
+            Int64(x,e) => KType::Int64(x,e),
+            Prim(x) => KType::Prim(x),
+            Unit => KType::Unit,
+            Never => KType::Never,
+            Unknown => KType::Unknown,
+            Scalar(x) => *x,
+        }
+    }
 }
 
 #[derive(Serialize,Deserialize)]
@@ -62,11 +132,61 @@ impl Default for KScalarType {
 #[derive(Debug)]
 pub enum KPrimType {
     Bool,
+    I8,
+    I16,
     I32,
     I64,
+    I128,
+    ISize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    USize,
     F32,
     F64,
+    /// A Unicode scalar value. Serialized as a single-character string.
+    Char,
+    String,
+}
+impl KPrimType {
+    pub fn is_integer(&self) -> bool {
+        use KPrimType::*;
+        matches!(self, I8 | I16 | I32 | I64 | I128 | ISize | U8 | U16 | U32 | U64 | U128 | USize)
+    }
+    /// Integers which cannot be represented precisely in JavaScript `number`.
+    pub fn is_64bit(&self) -> bool {
+        use KPrimType::*;
+        matches!(self, I64 | ISize | U64 | USize)
+    }
+    pub fn is_float(&self) -> bool {
+        matches!(self, KPrimType::F32 | KPrimType::F64)
+    }
+}
+
+/// Wire encoding of 64-bit integers.
+#[derive(strum_macros::EnumString)]
+#[derive(Serialize,Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Clone, Copy)]
+#[derive(Debug)]
+pub enum KInt64Encoding {
+    /// JSON number.
+    /// TypeScript gets `number` which loses precision beyond 2^53.
+    #[strum(serialize="number")]
+    #[serde(rename="number")]
+    Number,
+    /// JSON number.
+    /// TypeScript gets `bigint`, and needs a JSON parser which supports it.
+    #[strum(serialize="bigint")]
+    #[serde(rename="bigint")]
+    BigInt,
+    /// Decimal string.
+    /// Rust side needs `#[serde(with="...")]` to produce this.
+    #[strum(serialize="string")]
+    #[serde(rename="string")]
     String,
 }
 
-pub type KTypeName = String;
\ No newline at end of file
+pub type KTypeName = String;
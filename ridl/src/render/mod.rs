@@ -2,3 +2,4 @@ pub mod ridl1;
 pub mod openapi3;
 pub mod swift5;
 pub mod typescript4;
+pub mod rust;
@@ -5,6 +5,7 @@ use crate::model::*;
 use crate::model::log::*;
 use super::model as oa;
 
+mod operation;
 mod response;
 
 pub fn render_openapi3(x:&KMod) -> Result<String> {
@@ -25,26 +26,97 @@ impl KMod {
         doc.info.title = self.name.clone();
         let comps = doc.components.ridl_get_or_insert_default();
         let schemas = comps.schemas.ridl_get_or_insert_default();
-        for (name,schema) in self.render_items()? {
+        let mut referred = std::collections::HashSet::new();
+        self.collect_referred_names(&mut referred);
+        for (name,schema) in self.render_items(&referred)? {
             schemas.insert(name, schema);
         }
+        for (name,schema) in self.render_generic_instances()? {
+            schemas.insert(name, schema);
+        }
+        operation::render_messages(self, comps)?;
+        doc.paths = operation::render_paths(self)?;
         Ok(doc)
     }
-    fn render_items(&self) -> Result<Vec<(String,oa::ReferencedOrInlineSchema)>> {
+    /// OpenAPI3 has no generics.
+    /// Renders a concrete copy of generic definition for each application found.
+    fn render_generic_instances(&self) -> Result<Vec<(String,oa::ReferencedOrInlineSchema)>> {
+        let mut defs = Map::new();
+        self.collect_generic_defs(&mut defs);
+        let mut apps = Vec::<(String,Vec<KType>)>::new();
+        for item in self.items.iter() {
+            if item.type_params().is_empty() {
+                item.for_each_type(&mut |x| x.for_each_app(&mut |name,args| apps.push((name.to_string(), args.to_vec()))));
+            }
+        }
+        let mut xs = Vec::new();
+        let mut done = std::collections::HashSet::new();
+        let mut i = 0;
+        while i < apps.len() {
+            let (name,args) = apps[i].clone();
+            i += 1;
+            let instance_name = make_instance_name(&name, &args);
+            if !done.insert(instance_name.clone()) { continue }
+            let def: &KItem = match defs.get(name.as_str()) {
+                None => return err(self.span, &format!("unknown generic type `{}`", name)),
+                Some(x) => x,
+            };
+            if def.type_params().len() != args.len() { return err(*def.span(), &format!("`{}` needs {} type arguments", name, def.type_params().len())) }
+            let instance = match def.instantiate(&instance_name, &args) {
+                None => return err(*def.span(), &format!("unsupported type pattern in `{}`", instance_name)),
+                Some(x) => x,
+            };
+            instance.for_each_type(&mut |x| x.for_each_app(&mut |name,args| apps.push((name.to_string(), args.to_vec()))));
+            let schema = match &instance {
+                KItem::New(x) => x.render()?,
+                KItem::Nominal(x) => x.render()?,
+                KItem::Sum(x) => x.render()?,
+                KItem::Prod(x) => x.render()?,
+                _ => continue,
+            };
+            xs.push((instance_name, schema));
+        }
+        Ok(xs)
+    }
+    fn collect_generic_defs<'a>(&'a self, defs:&mut Map<&'a str, &'a KItem>) {
+        for item in self.items.iter() {
+            match item {
+                KItem::Mod(x) => x.collect_generic_defs(defs),
+                _ if !item.type_params().is_empty() => { defs.insert(item.name(), item); },
+                _ => (),
+            }
+        }
+    }
+    /// Collects names referenced by storage of type definitions.
+    /// Endpoint inputs and outputs are not counted.
+    fn collect_referred_names(&self, names:&mut std::collections::HashSet<String>) {
+        for item in self.items.iter() {
+            match item {
+                KItem::Mod(x) => x.collect_referred_names(names),
+                KItem::Func(_) | KItem::Service(_) => (),
+                _ => item.for_each_type(&mut |x| x.for_each_ref(&mut |name,_| { names.insert(name.to_string()); })),
+            }
+        }
+    }
+    fn render_items(&self, referred:&std::collections::HashSet<String>) -> Result<Vec<(String,oa::ReferencedOrInlineSchema)>> {
         let mut xs = Vec::new();
         for item in self.items.iter() {
             use KItem::*;
+            if !item.type_params().is_empty() { continue } // Rendered for each application.
             let (name,rendered_item) = match item {
                 Mod(x) => {
-                    let subitems = x.render_items()?;
+                    let subitems = x.render_items(referred)?;
                     xs.extend(subitems);
                     continue;
                 },
                 New(x) => (&x.name, x.render()?),
+                Nominal(x) => (&x.name, x.render()?),
                 Enum(x) => (&x.name, x.render()?),
                 Sum(x) => (&x.name, x.render()?),
+                // Rendered as parameters and request bodies, unless other definitions refer to it.
+                Prod(x) if x.attrs.rest.contains(&KAttrREST::MessageIn) && !referred.contains(&x.name) => continue,
                 Prod(x) => (&x.name, x.render()?),
-                Func(_) => continue,
+                Func(_) | Service(_) => continue,
             };
             xs.push((name.clone(), rendered_item));
         }
@@ -59,15 +131,46 @@ impl KNewType {
     }
 }
 
+#[ext(name=KNominalTypeOpenAPI3Rendering)]
+impl KNominalType {
+    /// Same schema with origin, but as a named component.
+    fn render(&self) -> Result<oa::ReferencedOrInlineSchema> {
+        let mut k = match self.origin.render(self.span)? {
+            oa::ReferencedOrInlineSchema::Inline(k) => k,
+            x@oa::ReferencedOrInlineSchema::Referenced(_) => {
+                let mut k = oa::Schema::default();
+                k.all_of = Some(vec![x]);
+                k
+            },
+        };
+        k.title.set(&self.name);
+        k.description.set(self.comment.trim());
+        Ok(oa::ReferencedOrInlineSchema::Inline(k))
+    }
+}
+
 #[ext(name=KEnumTypeOpenAPI3Rendering)]
 impl KEnumType {
     fn render(&self) -> Result<oa::ReferencedOrInlineSchema> {
         let mut k = oa::Schema::default();
         k.title.set(&self.name);
         k.description.set(self.collect_all_comments().trim());
-        k.r#type.set("string");
-        fn json(x:&KEnumTypeCase) -> serde_json::Value { serde_json::Value::String(x.name.clone()) }
-        k.r#enum = Some(self.cases.iter().map(json).collect());
+        match &self.repr {
+            None => {
+                k.r#type.set("string");
+                fn json(x:&KEnumTypeCase) -> serde_json::Value { serde_json::Value::String(x.name.clone()) }
+                k.r#enum = Some(self.cases.iter().map(json).collect());
+            },
+            Some(repr) => {
+                let r = repr.render();
+                k.r#type = r.r#type;
+                k.format = r.format;
+                k.r#enum = Some(self.cases.iter().map_collect_result(|x| match x.value {
+                    Some(v) => Ok(serde_json::Value::from(v)),
+                    None => err(x.span, "missing discriminant of integer-represented enum case"),
+                })?);
+            },
+        }
         Ok(oa::ReferencedOrInlineSchema::Inline(k))
     }
     fn collect_all_comments(&self) -> String {
@@ -88,13 +191,32 @@ impl KSumType {
         match &self.serialization {
             NameBased => self.render_name_based_form(),
             TypeBased { discriminant: x } => self.render_type_based_form(x),
+            Adjacent { tag, content } => self.render_adjacent_form(tag, content),
+            Untagged => self.render_untagged_form(),
         }
     }
-    fn render_name_based_form(&self) -> Result<oa::ReferencedOrInlineSchema> {
+    fn render_adjacent_form(&self, tag_prop_name:&str, content_prop_name:&str) -> Result<oa::ReferencedOrInlineSchema> {
         let mut k = oa::Schema::default();
         k.title.set(&self.name);
         k.description.set(self.comment.trim());
         k.r#type.set("object");
+        k.one_of = Some(self.variants.iter().map_collect_result(|x| x.render_adjacent_form(tag_prop_name, content_prop_name))?);
+        Ok(oa::ReferencedOrInlineSchema::Inline(k))
+    }
+    fn render_untagged_form(&self) -> Result<oa::ReferencedOrInlineSchema> {
+        let mut k = oa::Schema::default();
+        k.title.set(&self.name);
+        k.description.set(self.comment.trim());
+        // Decoder picks first matching variant. Therefore, multiple matches are allowed.
+        k.any_of = Some(self.variants.iter().map_collect_result(KSumTypeVariant::render_untagged_form)?);
+        Ok(oa::ReferencedOrInlineSchema::Inline(k))
+    }
+    fn render_name_based_form(&self) -> Result<oa::ReferencedOrInlineSchema> {
+        let mut k = oa::Schema::default();
+        k.title.set(&self.name);
+        k.description.set(self.comment.trim());
+        // Unit variants are strings.
+        if self.variants.iter().all(|x| x.form != KSumTypeVariantForm::Unit) { k.r#type.set("object") }
         k.one_of = Some(self.variants.iter().map_collect_result(KSumTypeVariant::render_name_based_form)?);
         Ok(oa::ReferencedOrInlineSchema::Inline(k))
     }
@@ -103,27 +225,118 @@ impl KSumType {
         k.title.set(&self.name);
         k.description.set(self.comment.trim());
         k.r#type.set("object");
-        k.one_of = Some(self.variants.iter().map_collect_result(KSumTypeVariant::render_type_based_form)?);
+        k.one_of = Some(self.variants.iter().map_collect_result(|x| x.render_type_based_form(discriminant_prop_name))?);
         let d = k.discriminator.ridl_get_or_insert_default();
         d.property_name = discriminant_prop_name.to_string();
+        let mut mapping = oa::Map::new();
+        for variant in self.variants.iter() {
+            if let KType::Def(x) = &variant.content {
+                mapping.insert(variant.name.clone(), make_opanapi3_ref(x));
+            }
+        }
+        d.mapping = Some(mapping);
         Ok(oa::ReferencedOrInlineSchema::Inline(k))
     }
 }
 
 #[ext(name=KSumTypeVariantOpenAPI3Rendering)]
 impl KSumTypeVariant {
+    /// Schema of serialized payload.
+    /// `None` for unit variants.
+    fn render_payload(&self) -> Result<Option<oa::ReferencedOrInlineSchema>> {
+        match &self.form {
+            KSumTypeVariantForm::Newtype => Ok(Some(self.content.render(self.span)?)),
+            KSumTypeVariantForm::Unit => Ok(None),
+            KSumTypeVariantForm::Tuple(xs) => {
+                // OpenAPI 3.0 cannot describe each position. Allows any of element types at all positions.
+                let mut items = Vec::new();
+                for x in xs.iter() {
+                    let item = x.render(self.span)?;
+                    if !items.contains(&item) { items.push(item) }
+                }
+                let mut k = oa::Schema::default();
+                k.r#type.set("array");
+                k.items = Some(Box::new(if items.len() == 1 { items.remove(0) } else {
+                    let mut k = oa::Schema::default();
+                    k.any_of = Some(items);
+                    oa::ReferencedOrInlineSchema::Inline(k)
+                }));
+                k.min_items = Some(xs.len() as u64);
+                k.max_items = Some(xs.len() as u64);
+                Ok(Some(oa::ReferencedOrInlineSchema::Inline(k)))
+            },
+            KSumTypeVariantForm::Struct(fields) => Ok(Some(oa::ReferencedOrInlineSchema::Inline(render_object(fields, self.span)?))),
+        }
+    }
+    /// Unit variants are bare strings.
     fn render_name_based_form(&self) -> Result<oa::ReferencedOrInlineSchema> {
         let mut x = oa::Schema::default();
         x.description.set(&self.comment);
+        match self.render_payload()? {
+            None => {
+                x.r#type.set("string");
+                x.r#enum = Some(vec![serde_json::Value::String(self.name.clone())]);
+            },
+            Some(p) => {
+                x.required = Some(vec![self.name.clone()]);
+                let ps = x.properties.ridl_get_or_insert_default();
+                ps.insert(self.name.clone(), p);
+            },
+        }
+        Ok(oa::ReferencedOrInlineSchema::Inline(x))
+    }
+    /// Unit variants are `null`.
+    fn render_untagged_form(&self) -> Result<oa::ReferencedOrInlineSchema> {
+        match self.render_payload()? {
+            Some(x) => Ok(x),
+            None => {
+                let mut x = oa::Schema::default();
+                x.nullable = Some(true);
+                x.r#enum = Some(vec![serde_json::Value::Null]);
+                Ok(oa::ReferencedOrInlineSchema::Inline(x))
+            },
+        }
+    }
+    fn render_adjacent_form(&self, tag_prop_name:&str, content_prop_name:&str) -> Result<oa::ReferencedOrInlineSchema> {
+        let mut tag = oa::Schema::default();
+        tag.r#type.set("string");
+        tag.r#enum = Some(vec![serde_json::Value::String(self.name.clone())]);
+        let mut x = oa::Schema::default();
+        x.description.set(&self.comment);
+        x.r#type.set("object");
+        let payload = self.render_payload()?;
+        // Unit variants have no content.
+        x.required = Some(match &payload {
+            None => vec![tag_prop_name.to_string()],
+            Some(_) => vec![tag_prop_name.to_string(), content_prop_name.to_string()],
+        });
         let ps = x.properties.ridl_get_or_insert_default();
-        let p = self.content.render(self.span)?;
-        ps.insert(self.name.clone(), p);
+        ps.insert(tag_prop_name.to_string(), oa::ReferencedOrInlineSchema::Inline(tag));
+        if let Some(p) = payload { ps.insert(content_prop_name.to_string(), p); }
         Ok(oa::ReferencedOrInlineSchema::Inline(x))
     }
-    fn render_type_based_form(&self) -> Result<oa::ReferencedOrInlineSchema> {
-        if self.content.is_vector() == true { return err(self.span, "vector-type is not supported in type-based sum-type") }
-        if self.content.is_option() == true { return err(self.span, "option-type is not supported in type-based sum-type") }
-        self.content.render(self.span)
+    /// Unit and struct variants are inline objects with tag property.
+    fn render_type_based_form(&self, discriminant_prop_name:&str) -> Result<oa::ReferencedOrInlineSchema> {
+        let fields = match &self.form {
+            KSumTypeVariantForm::Unit => &[][..],
+            KSumTypeVariantForm::Struct(fields) => &fields[..],
+            _ => {
+                if self.content.is_vector() == true { return err(self.span, "vector-type is not supported in type-based sum-type") }
+                if self.content.is_option() == true { return err(self.span, "option-type is not supported in type-based sum-type") }
+                return self.content.render(self.span)
+            },
+        };
+        let mut tag = oa::Schema::default();
+        tag.r#type.set("string");
+        tag.r#enum = Some(vec![serde_json::Value::String(self.name.clone())]);
+        let mut x = render_object(fields, self.span)?;
+        x.description.set(&self.comment);
+        x.required.ridl_get_or_insert_default().insert(0, discriminant_prop_name.to_string());
+        let mut ps = oa::Map::new();
+        ps.insert(discriminant_prop_name.to_string(), oa::ReferencedOrInlineSchema::Inline(tag));
+        ps.extend(x.properties.take().unwrap_or_default());
+        x.properties = Some(ps);
+        Ok(oa::ReferencedOrInlineSchema::Inline(x))
     }
 }
 
@@ -133,18 +346,31 @@ impl KProdType {
         let mut k = oa::Schema::default();
         k.title.set(&self.name);
         k.description.set(self.comment.trim());
-        k.r#type.set("object");
-        let reqs = k.required.ridl_get_or_insert_default();
-        let mut propks = oa::Map::new();
-        for field in self.fields.iter() {
-            let propk = field.content.render(self.span)?;
-            if !field.content.is_option() { reqs.push(field.name.clone()) }
-            propks.insert(field.name.clone(), propk);
-        }
-        k.properties.set(propks);
+        let o = render_object(&self.fields, self.span)?;
+        k.r#type = o.r#type;
+        k.required = o.required;
+        k.properties = o.properties;
         Ok(oa::ReferencedOrInlineSchema::Inline(k))
     }
 }
+/// Object schema with `fields` as properties.
+fn render_object(fields:&[KProdTypeField], span:KSpan) -> Result<oa::Schema> {
+    let mut k = oa::Schema::default();
+    k.r#type.set("object");
+    let reqs = k.required.ridl_get_or_insert_default();
+    let mut propks = oa::Map::new();
+    for field in fields.iter() {
+        // Top-level optionality is encoded in `required`.
+        let propk = match &field.content {
+            KType::Option(x) => x.render(span)?,
+            x => x.render(span)?,
+        };
+        if !field.is_omittable() { reqs.push(field.name.clone()) }
+        propks.insert(field.name.clone(), propk);
+    }
+    k.properties.set(propks);
+    Ok(k)
+}
 
 #[ext(name=KTypeOpenAPI3Rendering)]
 impl KType {
@@ -156,37 +382,122 @@ impl KType {
                 k.r#type.set("array");
                 Ok(oa::ReferencedOrInlineSchema::Inline(k))
             },
-            KType::Option(x) => x.render(span), // Optinality need to be defined in parent node.
-            KType::Scalar(x) => x.render(span),
+            KType::Map(_,v) => {
+                let mut k = oa::Schema::default();
+                k.r#type.set("object");
+                k.additional_properties = Box::new(match v.render(span)? {
+                    oa::ReferencedOrInlineSchema::Referenced(x) => oa::AdditionalProperties::Referenced(x),
+                    oa::ReferencedOrInlineSchema::Inline(x) => oa::AdditionalProperties::Inline(x),
+                });
+                Ok(oa::ReferencedOrInlineSchema::Inline(k))
+            },
+            KType::Set(x) => {
+                let mut k = oa::Schema::default();
+                k.items = Some(Box::new(x.render(span)?));
+                k.r#type.set("array");
+                k.unique_items = Some(true);
+                Ok(oa::ReferencedOrInlineSchema::Inline(k))
+            },
+            KType::Boxed(x) => x.render(span),
+            KType::Option(x) => {
+                let k = match x.render(span)? {
+                    oa::ReferencedOrInlineSchema::Inline(mut k) => { k.nullable = Some(true); k },
+                    // Siblings of `$ref` are ignored. Therefore, wrap it.
+                    x@oa::ReferencedOrInlineSchema::Referenced(_) => {
+                        let mut k = oa::Schema::default();
+                        k.all_of = Some(vec![x]);
+                        k.nullable = Some(true);
+                        k
+                    },
+                };
+                Ok(oa::ReferencedOrInlineSchema::Inline(k))
+            },
+            KType::Def(x) => Ok(oa::ReferencedOrInlineSchema::Referenced(oa::Reference { r#ref: make_opanapi3_ref(&x) })),
+            KType::App(x,args) => Ok(oa::ReferencedOrInlineSchema::Referenced(oa::Reference { r#ref: make_opanapi3_ref(&make_instance_name(x, args)) })),
+            KType::Int64(_,KInt64Encoding::String) => {
+                let mut k = oa::Schema::default();
+                k.r#type.set("string");
+                k.format.set("int64");
+                Ok(oa::ReferencedOrInlineSchema::Inline(k))
+            },
+            KType::Int64(x,_) => Ok(oa::ReferencedOrInlineSchema::Inline(x.render())),
+            KType::Prim(x) => Ok(oa::ReferencedOrInlineSchema::Inline(x.render())),
+            KType::Unit => return err(span, "unit-type (`()`) is not supported"),
             KType::Never => return err(span, "never-type is not unsupported"),
             KType::Unknown => return err(span, "unsupported type pattern"),
         }
     }
 }
 
-#[ext(name=KScalarTypeOpenAPI3Rendering)]
-impl KScalarType {
-    fn render(&self, span:KSpan) -> Result<oa::ReferencedOrInlineSchema> {
-        fn inline(r#type:&str, format:&str) -> Result<oa::ReferencedOrInlineSchema> {
+#[ext(name=KPrimTypeOpenAPI3Rendering)]
+impl KPrimType {
+    fn render(&self) -> oa::Schema {
+        fn inline(r#type:&str, format:&str) -> oa::Schema {
             let mut k = oa::Schema::default();
             k.r#type.set(r#type);
             k.format = if format.is_empty() { None } else { Some(format.to_string()) };
-            Ok(oa::ReferencedOrInlineSchema::Inline(k))
+            k
+        }
+        fn ranged(format:&str, min:impl Into<serde_json::Number>, max:Option<serde_json::Number>) -> oa::Schema {
+            let mut k = inline("integer", format);
+            k.minimum = Some(min.into());
+            k.maximum = max;
+            k
         }
-        use KScalarType::*;
         use KPrimType::*;
         match self {
-            Unit => return err(span, "unit-type (`()`) is not supported"),
-            Prim(Bool) => inline("boolean", ""),
-            Prim(I32) => inline("integer", "int32"),
-            Prim(I64) => inline("integer", "int64"),
-            Prim(F32) => inline("number", "float"),
-            Prim(F64) => inline("number", "double"),
-            Prim(String) => inline("string", ""),
-            Def(x) => Ok(oa::ReferencedOrInlineSchema::Referenced(oa::Reference { r#ref: make_opanapi3_ref(&x) })),
+            Bool => inline("boolean", ""),
+            I8 => ranged("int32", i8::MIN, Some(i8::MAX.into())),
+            I16 => ranged("int32", i16::MIN, Some(i16::MAX.into())),
+            I32 => inline("integer", "int32"),
+            I64 => inline("integer", "int64"),
+            ISize => inline("integer", "int64"),
+            // No format for 128-bit integers.
+            I128 => inline("integer", ""),
+            U8 => ranged("int32", 0, Some(u8::MAX.into())),
+            U16 => ranged("int32", 0, Some(u16::MAX.into())),
+            U32 => ranged("int64", 0, Some(u32::MAX.into())),
+            // `int64` is signed, and cannot have the maximum.
+            U64 => ranged("", 0, Some(u64::MAX.into())),
+            USize => ranged("", 0, Some(u64::MAX.into())),
+            U128 => ranged("", 0, None),
+            F32 => inline("number", "float"),
+            F64 => inline("number", "double"),
+            Char => {
+                let mut k = inline("string", "");
+                k.min_length = Some(1);
+                k.max_length = Some(1);
+                k
+            },
+            String => inline("string", ""),
         }
     }
 }
+/// Makes a deterministic name for an instance of generic definition.
+/// e.g. `Page<Order>` becomes `Page_Order`.
+fn make_instance_name(name:&str, args:&[KType]) -> String {
+    let mut s = name.to_string();
+    for x in args.iter() {
+        s.push('_');
+        s.push_str(&make_type_name(x));
+    }
+    s
+}
+fn make_type_name(x:&KType) -> String {
+    match x {
+        KType::Vector(x) => format!("Vec_{}", make_type_name(x)),
+        KType::Option(x) => format!("Option_{}", make_type_name(x)),
+        KType::Map(k,v) => format!("Map_{}_{}", make_type_name(k), make_type_name(v)),
+        KType::Set(x) => format!("Set_{}", make_type_name(x)),
+        KType::Boxed(x) => make_type_name(x),
+        KType::Def(x) => x.clone(),
+        KType::App(x,args) => make_instance_name(x, args),
+        KType::Int64(x,_) | KType::Prim(x) => format!("{:?}", x),
+        KType::Unit => "Unit".to_string(),
+        KType::Never => "Never".to_string(),
+        KType::Unknown => "Unknown".to_string(),
+    }
+}
 fn make_opanapi3_ref(name:&str) -> String {
     format!("#/components/schemas/{}", name).to_string()
 }
@@ -0,0 +1,150 @@
+//! Renders REST endpoints into OAS Paths object.
+
+use crate::model::rest::{self,KEndpoint,KParamLocation};
+use super::oa;
+
+use crate::prelude::*;
+use super::*;
+
+type Defs<'a> = Map<&'a str, &'a KItem>;
+
+/// Renders all endpoints with REST method and path.
+/// Items without them are not HTTP endpoints and skipped.
+pub(super) fn render_paths(x:&KMod) -> Result<oa::Paths> {
+    let mut defs = Map::new();
+    x.collect_defs(&mut defs);
+    let mut paths = oa::Paths::new();
+    for endpoint in x.endpoints().iter() {
+        let (method,path) = (endpoint.method, endpoint.render_path(&defs));
+        let path = path.as_str();
+        if !paths.contains_key(path) { paths.insert(path.to_string(), oa::PathItem::default()); }
+        let slot = match paths.get_mut(path).unwrap().operation_mut(method) {
+            None => return err(endpoint.span, &format!("unsupported HTTP method `{}`", method)),
+            Some(x) => x,
+        };
+        if slot.is_some() { return err(endpoint.span, &format!("duplicate operation `{} {}`", method, path)) }
+        *slot = Some(endpoint.render(&defs)?);
+    }
+    Ok(paths)
+}
+
+/// Registers `#[rest(in)]` messages as reusable parameters and request bodies.
+/// Parameters are keyed by `{message}.{field}` and request bodies by message name.
+pub(super) fn render_messages(x:&KMod, comps:&mut oa::Components) -> Result<()> {
+    for item in x.items.iter() {
+        match item {
+            KItem::Mod(x) => render_messages(x, comps)?,
+            KItem::Prod(x) if x.attrs.rest.contains(&KAttrREST::MessageIn) => {
+                let (params,body) = render_message_in(x)?;
+                for (key,param) in params {
+                    comps.parameters.ridl_get_or_insert_default().insert(key, oa::Or::A(param));
+                }
+                if let Some(body) = body {
+                    comps.request_bodies.ridl_get_or_insert_default().insert(x.name.clone(), oa::Or::A(body));
+                }
+            },
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+#[ext(name=KEndpointOpenAPI3Rendering)]
+impl<'a> KEndpoint<'a> {
+    /// Path template with placeholders named same with path parameters.
+    fn render_path(&self, defs:&Defs) -> String {
+        let message = self.message_in(defs);
+        let path = rest::replace_placeholders(self.path, |name| Ok::<_,()>(match message.and_then(|x| x.path_field(name)) {
+            Some(x) => format!("{{{}}}", x.name),
+            None => format!("{{{}}}", name),
+        }));
+        path.unwrap_or_else(|_| self.path.to_string())
+    }
+    /// `#[rest(in)]` input is decomposed into parameters and request body.
+    /// `#[rest(out)]` output is decomposed into responses.
+    /// Any other input and output are JSON bodies.
+    fn render(&self, defs:&Defs) -> Result<oa::Operation> {
+        let mut k = oa::Operation::default();
+        k.tags = self.service.map(|x| vec![x.to_string()]);
+        k.description.set(self.comment.trim());
+        k.operation_id.set(self.name);
+        match self.message_in(defs) {
+            Some(x) => {
+                // Registered as components by `render_messages`.
+                let (params,body) = render_message_in(x)?;
+                if !params.is_empty() { k.parameters = Some(params.iter().map(|(key,_)| oa::Or::B(make_component_ref("parameters", key))).collect()) }
+                if body.is_some() { k.request_body = Some(oa::Or::B(make_component_ref("requestBodies", &x.name))) }
+            },
+            _ if *self.input == KType::Unit => (),
+            _ => {
+                let mut content = oa::Map::new();
+                content.insert("application/json".to_string(), oa::MediaType { schema: Some(self.input.render(self.span)?) });
+                k.request_body = Some(oa::Or::A(oa::RequestBody { description: None, content: content, required: true }));
+            },
+        }
+        if let Some(x) = self.message_out(defs) {
+            k.responses = response::render_message_out(x)?;
+            return Ok(k)
+        }
+        let mut response = oa::Response::default();
+        let status = match self.output {
+            KType::Unit => "204",
+            x => {
+                let mut content = oa::Map::new();
+                content.insert("application/json".to_string(), oa::MediaType { schema: Some(x.render(self.span)?) });
+                response.content = Some(content);
+                "200"
+            },
+        };
+        response.description = if status == "204" { "No Content" } else { "OK" }.to_string();
+        k.responses.insert(status.to_string(), oa::Or::A(response));
+        Ok(k)
+    }
+}
+
+fn make_component_ref(kind:&str, name:&str) -> oa::Reference {
+    oa::Reference { r#ref: format!("#/components/{}/{}", kind, name) }
+}
+
+/// Path, query, header and cookie fields become parameters keyed by `{message}.{field}`.
+/// Body fields become request body content keyed by their MIME-Types.
+fn render_message_in(x:&KProdType) -> Result<(Vec<(String,oa::Parameter)>, Option<oa::RequestBody>)> {
+    let mut params = Vec::new();
+    let mut body = None::<oa::RequestBody>;
+    for (field,location) in x.rest_params() {
+        // Top-level optionality is encoded in `required`.
+        let schema = match &field.content {
+            KType::Option(x) => x.render(field.span)?,
+            x => x.render(field.span)?,
+        };
+        let (location,name) = match location {
+            KParamLocation::Path => (oa::ParameterIn::Path, field.name.as_str()),
+            KParamLocation::Query => (oa::ParameterIn::Query, field.name.as_str()),
+            KParamLocation::Header(name) => (oa::ParameterIn::Header, name),
+            KParamLocation::Cookie(name) => (oa::ParameterIn::Cookie, name),
+            KParamLocation::Body(mime) => {
+                let body = body.get_or_insert_with(|| {
+                    let mut k = oa::RequestBody { description: None, content: oa::Map::new(), required: false };
+                    k.description.set(x.comment.trim());
+                    k
+                });
+                body.content.insert(mime.to_string(), oa::MediaType { schema: Some(schema) });
+                body.required |= !field.is_omittable();
+                continue;
+            },
+        };
+        let mut param = oa::Parameter {
+            name: name.to_string(),
+            // Path parameters are always required.
+            required: location == oa::ParameterIn::Path || !field.is_omittable(),
+            r#in: location,
+            description: None,
+            deprecated: false,
+            allow_empty_value: false,
+            schema: Some(schema),
+        };
+        param.description.set(field.comment.trim());
+        params.push((format!("{}.{}", x.name, field.name), param));
+    }
+    Ok((params, body))
+}
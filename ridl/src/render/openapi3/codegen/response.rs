@@ -1,36 +1,47 @@
-use crate::model::*;
 use super::oa;
 
 use crate::prelude::*;
 use super::*;
 
-/// Renders OAS Response object if appropriate.
-fn render_item(item:&KItem) -> Result<Option<oa::Response>> {
-    let sum_type = if let KItem::Sum(sum_type) = item { sum_type } else { return Ok(None) };
-    if sum_type.attrs.rest.contains(&KAttrREST::MessageOut) {} else { return Ok(None) }
-
-    let mut oa_content_map = Map::<oa::MIMEType, oa::MediaType>::new();
+/// Renders OAS Responses object of a `#[rest(out)]` sum-type.
+/// Variants are grouped by status code.
+/// Variants of same status become media types of one response.
+pub(super) fn render_message_out(sum_type:&KSumType) -> Result<oa::Responses> {
+    let mut oa_responses = oa::Responses::new();
+    let mut descriptions = Map::<String, Vec<String>>::new();
     for variant in sum_type.variants.iter() {
-        let oa_mime = if let Some(mime) = mime(variant) { mime } else { return err(variant.span, "missing MIME-Type attribute") };
-        let mut oa_media_type = oa::MediaType::default();
-        oa_media_type.schema = Some(variant.content.render(variant.span)?);
-        oa_content_map.insert(oa_mime.to_string(), oa_media_type);
-    }
+        let oa_status = if let Some(status) = variant.attrs.rest_status_key() { status } else { return err(variant.span, "missing status attribute") };
+        if !oa_responses.contains_key(&oa_status) {
+            oa_responses.insert(oa_status.clone(), oa::Or::A(oa::Response::default()));
+            descriptions.insert(oa_status.clone(), Vec::new());
+        }
+        let oa_response = if let Some(oa::Or::A(x)) = oa_responses.get_mut(&oa_status) { x } else { unreachable!() };
+        let description = match variant.comment.trim() {
+            "" => variant.name.clone(),
+            x => x.to_string(),
+        };
+        let descriptions = descriptions.get_mut(&oa_status).unwrap();
+        if !descriptions.contains(&description) { descriptions.push(description) }
+        oa_response.description = descriptions.join("\n");
 
-    let mut oa_response = oa::Response::default();
-    oa_response.content = Some(oa_content_map);
-    Ok(Some(oa_response))
-}
-
-fn status(x:&KSumTypeVariant) -> Option<&i64> {
-    for a in x.attrs.rest.iter() {
-        if let KAttrREST::Status(z) = a { return Some(z) }
+        if let Some(schema) = variant.render_payload()? {
+            let oa_mime = if let Some(mime) = variant.attrs.rest_mime() { mime } else { return err(variant.span, "missing MIME-Type attribute") };
+            let oa_content_map = oa_response.content.ridl_get_or_insert_default();
+            if oa_content_map.contains_key(oa_mime) { return err(variant.span, &format!("duplicate response for status `{}` and MIME-Type `{}`", oa_status, oa_mime)) }
+            let mut oa_media_type = oa::MediaType::default();
+            oa_media_type.schema = Some(schema);
+            oa_content_map.insert(oa_mime.to_string(), oa_media_type);
+        }
+        for name in variant.attrs.rest_headers() {
+            let name = if let Some(name) = name { name } else { return err(variant.span, "response header needs a name") };
+            let oa_headers = oa_response.headers.ridl_get_or_insert_default();
+            if oa_headers.contains_key(name) { continue }
+            // Header values are strings on the wire.
+            let mut oa_schema = oa::Schema::default();
+            oa_schema.r#type.set("string");
+            let oa_header = oa::Header { description: None, required: false, deprecated: false, schema: Some(oa::ReferencedOrInlineSchema::Inline(oa_schema)) };
+            oa_headers.insert(name.to_string(), oa::Or::A(oa_header));
+        }
     }
-    return None
+    Ok(oa_responses)
 }
-fn mime(x:&KSumTypeVariant) -> Option<&str> {
-    for a in x.attrs.rest.iter() {
-        if let KAttrREST::MIME(z) = a { return Some(z) }
-    }
-    return None
-}
\ No newline at end of file
@@ -0,0 +1,22 @@
+use serde::{Serialize,Deserialize};
+use serde_with::skip_serializing_none;
+
+use super::is_default;
+use super::ReferencedOrInlineSchema;
+
+/// Same with `Parameter` except `name` and `in`.
+#[skip_serializing_none]
+#[derive(Eq, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[derive(Debug)]
+#[serde(rename_all="camelCase")]
+pub struct Header {
+    pub description: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub required: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub deprecated: bool,
+    pub schema: Option<ReferencedOrInlineSchema>,
+}
@@ -6,7 +6,9 @@
 //! Unsupported features will cause an error.
 
 mod info;
+mod operation;
 mod parameter;
+mod header;
 mod request_body;
 mod response;
 mod media_type;
@@ -16,7 +18,9 @@ use serde_derive::{Serialize, Deserialize};
 use serde_with::skip_serializing_none;
 
 pub use info::*;
+pub use operation::*;
 pub use parameter::*;
+pub use header::*;
 pub use request_body::*;
 pub use response::*;
 pub use media_type::*;
@@ -81,17 +85,33 @@ pub struct PathItem {
     r#ref: Option<String>,
     summary: Option<String>,
     description: Option<String>,
-    // get: Option<Operation>,
-    // put: Option<Operation>,
-    // post: Option<Operation>,
-    // delete: Option<Operation>,
-    // options: Option<Operation>,
-    // head: Option<Operation>,
-    // patch: Option<Operation>,
-    // trace: Option<Operation>,
+    pub get: Option<Operation>,
+    pub put: Option<Operation>,
+    pub post: Option<Operation>,
+    pub delete: Option<Operation>,
+    pub options: Option<Operation>,
+    pub head: Option<Operation>,
+    pub patch: Option<Operation>,
+    pub trace: Option<Operation>,
     // servers: Option<Vec<Server>>,
     // parameters: Option<ReferenceOrParameter>,
 }
+impl PathItem {
+    /// Operation slot for an HTTP method in upper case. (e.g. `GET`)
+    pub fn operation_mut(&mut self, method:&str) -> Option<&mut Option<Operation>> {
+        match method {
+            "GET" => Some(&mut self.get),
+            "PUT" => Some(&mut self.put),
+            "POST" => Some(&mut self.post),
+            "DELETE" => Some(&mut self.delete),
+            "OPTIONS" => Some(&mut self.options),
+            "HEAD" => Some(&mut self.head),
+            "PATCH" => Some(&mut self.patch),
+            "TRACE" => Some(&mut self.trace),
+            _ => None,
+        }
+    }
+}
 
 #[skip_serializing_none]
 #[derive(Eq, PartialEq)]
@@ -196,3 +216,4 @@ pub type SchemaOrReference = Or<Schema,Reference>;
 pub type ResponseOrReference = Or<Response,Reference>;
 pub type ParameterOrReference = Or<Parameter,Reference>;
 pub type RequestBodyOrReference = Or<RequestBody,Reference>;
+pub type HeaderOrReference = Or<Header,Reference>;
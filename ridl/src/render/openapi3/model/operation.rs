@@ -0,0 +1,28 @@
+use serde::{Serialize,Deserialize};
+use serde_with::skip_serializing_none;
+
+use crate::prelude::*;
+use super::is_default;
+use super::{ParameterOrReference,RequestBodyOrReference,ResponseOrReference};
+
+#[skip_serializing_none]
+#[derive(Eq, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[derive(Default)]
+#[derive(Debug)]
+#[serde(rename_all="camelCase")]
+pub struct Operation {
+    pub tags: Option<List<String>>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub operation_id: Option<String>,
+    pub parameters: Option<List<ParameterOrReference>>,
+    pub request_body: Option<RequestBodyOrReference>,
+    pub responses: Responses,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub deprecated: bool,
+}
+
+/// Keyed by HTTP status code or `default`.
+pub type Responses = Map<String,ResponseOrReference>;
@@ -17,15 +17,3 @@ pub struct RequestBody {
     #[serde(skip_serializing_if = "is_default")]
     pub required: bool,
 }
-
-#[skip_serializing_none]
-#[derive(Eq, PartialEq)]
-#[derive(Serialize, Deserialize)]
-#[derive(Debug)]
-#[serde(rename_all="camelCase")]
-pub enum ParameterIn {
-    Query,
-    Header,
-    Path,
-    Cookie,
-}
@@ -2,7 +2,7 @@ use serde::{Serialize,Deserialize};
 use serde_with::skip_serializing_none;
 
 use crate::prelude::*;
-use super::{MIMEType,MediaType};
+use super::{MIMEType,MediaType,HeaderOrReference};
 
 #[skip_serializing_none]
 #[derive(Eq, PartialEq)]
@@ -12,5 +12,6 @@ use super::{MIMEType,MediaType};
 #[serde(rename_all="camelCase")]
 pub struct Response {
     pub description: String,
+    pub headers: Option<Map<String,HeaderOrReference>>,
     pub content: Option<Map<MIMEType,MediaType>>,
 }
@@ -30,12 +30,20 @@ pub struct Schema {
     pub any_of: Option<Vec<ReferencedOrInlineSchema>>,
     pub not: Option<Box<ReferencedOrInlineSchema>>,
     pub items: Option<Box<ReferencedOrInlineSchema>>,
+    pub min_items: Option<u64>,
+    pub max_items: Option<u64>,
+    pub unique_items: Option<bool>,
     pub properties: Option<Map<String, ReferencedOrInlineSchema>>,
     #[serde(default)]
     #[serde(skip_serializing_if = "is_default")]
     pub additional_properties: Box<AdditionalProperties>,
     pub description: Option<String>,
     pub format: Option<String>,
+    pub minimum: Option<serde_json::Number>,
+    pub maximum: Option<serde_json::Number>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub nullable: Option<bool>,
     pub default: Option<serde_json::Value>,
 
     pub discriminator: Option<Discriminator>,
@@ -0,0 +1,313 @@
+//! HTTP client of REST endpoints.
+//! Uses the Rust types which the schema was scanned from.
+
+use indoc::indoc;
+
+use crate::prelude::*;
+use crate::model::rest::{self, KEndpoint, KParamLocation};
+use super::*;
+
+type Defs<'a> = Map<&'a str, &'a KItem>;
+
+pub fn render_rust_client(x:&KMod) -> Result<String> {
+    let mut defs = Map::new();
+    x.collect_defs(&mut defs);
+    // Methods of `Client` itself are reserved.
+    let endpoints = x.named_endpoints(|x| x.to_string().to_snake(), &["new", "send"])?;
+    Ok(format!(
+        indoc!(r#"
+            //! HTTP client of REST endpoints.
+            //! Place this as a child module of the module defining the types.
+
+            // Not all of runtime functions are used by every schema.
+            #![allow(dead_code)]
+
+            use std::future::Future;
+
+            use serde::Serialize;
+            use serde::de::DeserializeOwned;
+
+            use super::*;
+
+            /// HTTP client of REST endpoints.
+            pub struct Client<T> {{
+                pub base_url: String,
+                pub transport: T,
+            }}
+
+            impl<T: Transport> Client<T> {{
+                pub fn new(base_url: impl Into<String>, transport: T) -> Self {{
+                    Client {{ base_url: base_url.into(), transport: transport }}
+                }}
+
+            {methods}
+
+                async fn send(&self, method: &'static str, path: String, query: Vec<(&str, String)>, headers: Vec<(&str, String)>, body: Vec<u8>) -> Result<Response, Error<T::Error>> {{
+                    let mut url = format!("{{}}{{}}", self.base_url.trim_end_matches('/'), path);
+                    for (i, (k, v)) in query.iter().enumerate() {{
+                        url.push(if i == 0 {{ '?' }} else {{ '&' }});
+                        url.push_str(&format!("{{}}={{}}", percent_encode(k), percent_encode(v)));
+                    }}
+                    let headers = headers.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+                    self.transport.send(Request {{ method: method, url: url, headers: headers, body: body }}).await.map_err(Error::Transport)
+                }}
+            }}
+
+            {runtime}
+        "#),
+        methods=endpoints.iter().map_join(|(name,x)| x.render_rust_client(name, &defs), "\n\n")?.indent(),
+        runtime=RUNTIME.trim(),
+    ))
+}
+
+#[ext(name=KEndpointRustClientRendering)]
+impl<'a> KEndpoint<'a> {
+    /// `#[rest(in)]` input is sent as parameters and body.
+    /// `#[rest(out)]` output is decoded by status code.
+    /// Any other input and output are JSON bodies.
+    fn render_rust_client(&self, name:&str, defs:&Defs) -> Result<String> {
+        let message_in = self.message_in(defs);
+        let mut args = Vec::new();
+        let path = rest::replace_placeholders(self.path, |name| match message_in.and_then(|x| x.path_field(name)) {
+            None => err(self.span, &format!("path placeholder `{{{}}}` needs a `#[path]` field of `#[rest(in)]` input", name)),
+            Some(x) => {
+                args.push(format!("path_value(&input.{})?", x.name));
+                Ok("{}".to_string())
+            },
+        })?;
+        let mut queries = Vec::new();
+        let mut headers = Vec::new();
+        let mut cookies = Vec::new();
+        let mut body = "let body = Vec::new();".to_string();
+        match message_in {
+            Some(x) => {
+                // Optional bodies are alternatives. The first present one is sent.
+                let mut bodies = Vec::new();
+                for (f,wire) in x.fields.iter().zip(x.serde_field_names()) {
+                    let name = f.rest_wire_name(&wire);
+                    match f.rest_location().filter(|_| !f.attrs.has_serde_skip()) {
+                        // Not on wire. Scanner has checked that other fields have locations.
+                        None => (),
+                        Some(KParamLocation::Path) => (),
+                        Some(KParamLocation::Query) => queries.push(render_optional_binding(f, |x| match f.content.unwrap_option() {
+                            KType::Vector(_) | KType::Set(_) => format!(r#"for x in {} {{ query.push(("{}", parameter_value(x)?)) }}"#, x, name),
+                            _ => format!(r#"query.push(("{}", parameter_value({})?));"#, name, x),
+                        })),
+                        Some(KParamLocation::Header(_)) => headers.push(render_optional_binding(f, |x| format!(r#"headers.push(("{}", parameter_value({})?));"#, name, x))),
+                        Some(KParamLocation::Cookie(_)) => cookies.push(render_optional_binding(f, |x| format!(r#"cookies.push(format!("{}={{}}", parameter_value({})?));"#, name, x))),
+                        Some(KParamLocation::Body(mime)) => match f.content.is_option() {
+                            true => bodies.push(format!(
+                                indoc!(r#"
+                                    if let Some(x) = &input.{name} {{
+                                        headers.push(("Content-Type", "{mime}".to_string()));
+                                        encode_body(x, "{mime}")?
+                                    }}
+                                "#),
+                                name=f.name,
+                                mime=mime,
+                            ).trim().to_string()),
+                            false => body = format!(
+                                indoc!(r#"
+                                    headers.push(("Content-Type", "{mime}".to_string()));
+                                    let body = encode_body(&input.{name}, "{mime}")?;
+                                "#),
+                                name=f.name,
+                                mime=mime,
+                            ).trim().to_string(),
+                        },
+                    }
+                }
+                if !bodies.is_empty() {
+                    bodies.push("{\n    Vec::new()\n}".to_string());
+                    body = format!("let body = {};", bodies.join(" else "));
+                }
+            },
+            None if *self.input == KType::Unit => (),
+            None => body = indoc!(r#"
+                headers.push(("Content-Type", "application/json".to_string()));
+                let body = encode_body(input, "application/json")?;
+            "#).trim().to_string(),
+        }
+        if !cookies.is_empty() {
+            headers.push(format!(
+                indoc!(r#"
+                    let mut cookies = Vec::<String>::new();
+                    {cookies}
+                    if !cookies.is_empty() {{ headers.push(("Cookie", cookies.join("; "))) }}
+                "#),
+                cookies=cookies.join("\n"),
+            ).trim().to_string());
+        }
+        let mut lines = Vec::new();
+        lines.push(format!("let {}query = Vec::<(&str, String)>::new();", if queries.is_empty() { "" } else { "mut " }));
+        lines.extend(queries);
+        let is_mut = !headers.is_empty() || body.contains("headers.push");
+        lines.push(format!("let {}headers = Vec::<(&str, String)>::new();", if is_mut { "mut " } else { "" }));
+        lines.extend(headers);
+        lines.push(body);
+        lines.push(format!(
+            r#"let response = self.send("{method}", {path}, query, headers, body).await?;"#,
+            method=self.method,
+            path=if args.is_empty() { format!("{:?}.to_string()", path) } else { format!("format!({:?}, {})", path, args.join(", ")) },
+        ));
+        lines.push(match self.message_out(defs) {
+            Some(x) => render_message_out_decoding(x)?,
+            None => render_default_decoding(self.output),
+        });
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                pub async fn {name}(&self{input}) -> Result<{output}, Error<T::Error>> {{
+                {lines}
+                }}
+            "#),
+            comment=self.comment.to_string().commentize(),
+            name=name,
+            input=match self.input {
+                KType::Unit => String::new(),
+                x => format!(", input: &{}", x.render(self.span)?),
+            },
+            output=self.output.render(self.span)?,
+            lines=lines.join("\n").indent(),
+        )).trim()
+    }
+}
+
+/// Renders a statement on the field, with its content bound if it is optional.
+fn render_optional_binding(f:&KProdTypeField, render:impl Fn(&str) -> String) -> String {
+    match f.content.is_option() {
+        true => format!("if let Some(x) = &input.{} {{ {} }}", f.name, render("x")),
+        false => render(&format!("&input.{}", f.name)),
+    }
+}
+
+/// Any 2xx status is a success.
+fn render_default_decoding(output:&KType) -> String {
+    let guard = "if !(200..300).contains(&response.status) { return Err(Error::Status(response)) }";
+    match output {
+        KType::Unit => format!("{}\nOk(())", guard),
+        _ => format!("{}\n{}", guard, r#"Ok(decode_body(&response.body, "application/json")?)"#),
+    }
+}
+
+/// Picks variant by status code, and then by MIME-Type if a status has multiple variants.
+/// `#[status(default)]` variants are tried last.
+fn render_message_out_decoding(x:&KSumType) -> Result<String> {
+    let (cases, is_exhaustive) = x.rest_response_cases()?;
+    let mut arms = Vec::new();
+    for case in cases.iter() {
+        let v = case.variant;
+        let value = match case.mime {
+            None => format!("{}::{}", x.name, v.name),
+            Some(mime) => format!(r#"{}::{}(decode_body(&response.body, "{}")?)"#, x.name, v.name, mime),
+        };
+        let pattern = match case.status {
+            None => "_".to_string(),
+            Some(status) => status.to_string(),
+        };
+        let guard = match case.content_type {
+            None => String::new(),
+            Some(mime) => format!(r#" if content_type.starts_with("{}")"#, mime),
+        };
+        arms.push(format!("{}{} => Ok({}),", pattern, guard, value));
+    }
+    if !is_exhaustive { arms.push("_ => Err(Error::Status(response)),".to_string()) }
+    let content_type = if cases.iter().any(|x| x.content_type.is_some()) {
+        "let content_type = response.header(\"Content-Type\").unwrap_or_default().to_string();\n"
+    } else {
+        ""
+    };
+    Ok(format!(
+        indoc!(r#"
+            {content_type}match response.status {{
+            {arms}
+            }}
+        "#),
+        content_type=content_type,
+        arms=arms.join("\n").indent(),
+    ).trim().to_string())
+}
+
+/// Transport and codecs used by endpoint methods.
+const RUNTIME: &str = indoc!(r#"
+    /// HTTP request made by `Client`.
+    #[derive(Debug)]
+    pub struct Request {
+        pub method: &'static str,
+        pub url: String,
+        pub headers: Vec<(String, String)>,
+        pub body: Vec<u8>,
+    }
+
+    /// HTTP response given to `Client`.
+    #[derive(Debug)]
+    pub struct Response {
+        pub status: u16,
+        pub headers: Vec<(String, String)>,
+        pub body: Vec<u8>,
+    }
+    impl Response {
+        /// Value of a header. Names are compared ignoring case.
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+        }
+    }
+
+    /// Sends HTTP requests.
+    /// Implement this with any HTTP library, or with a fake to test without network.
+    pub trait Transport {
+        type Error;
+        fn send(&self, request: Request) -> impl Future<Output = Result<Response, Self::Error>> + Send;
+    }
+
+    #[derive(Debug)]
+    pub enum Error<E> {
+        /// Transport failed to send a request.
+        Transport(E),
+        /// Response with a status code not declared by endpoint.
+        Status(Response),
+        /// Parameters or bodies cannot be encoded or decoded.
+        Codec(serde_json::Error),
+    }
+    impl<E> From<serde_json::Error> for Error<E> {
+        fn from(x: serde_json::Error) -> Self {
+            Error::Codec(x)
+        }
+    }
+
+    /// Strings are sent as is, and other values in JSON.
+    fn parameter_value<T: Serialize>(x: &T) -> serde_json::Result<String> {
+        match serde_json::to_value(x)? {
+            serde_json::Value::String(x) => Ok(x),
+            x => Ok(x.to_string()),
+        }
+    }
+    fn path_value<T: Serialize>(x: &T) -> serde_json::Result<String> {
+        Ok(percent_encode(&parameter_value(x)?))
+    }
+    fn percent_encode(x: &str) -> String {
+        let mut s = String::new();
+        for b in x.bytes() {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) { s.push(b as char) } else { s.push_str(&format!("%{:02X}", b)) }
+        }
+        s
+    }
+    /// Bytes and non-JSON strings are sent as is, and other values in JSON.
+    fn encode_body<T: Serialize>(x: &T, content_type: &str) -> serde_json::Result<Vec<u8>> {
+        let value = serde_json::to_value(x)?;
+        if !content_type.contains("json") {
+            if let serde_json::Value::String(x) = &value { return Ok(x.clone().into_bytes()) }
+            let bytes = value.as_array().and_then(|xs| xs.iter().map(|x| x.as_u64().and_then(|x| u8::try_from(x).ok())).collect::<Option<Vec<u8>>>());
+            if let Some(x) = bytes { return Ok(x) }
+        }
+        serde_json::to_vec(&value)
+    }
+    fn decode_body<T: DeserializeOwned>(body: &[u8], content_type: &str) -> serde_json::Result<T> {
+        if content_type.contains("json") { return serde_json::from_slice(body) }
+        let value = match content_type.starts_with("text/") {
+            true => serde_json::Value::String(String::from_utf8_lossy(body).into_owned()),
+            false => body.iter().map(|x| serde_json::Value::from(*x)).collect(),
+        };
+        serde_json::from_value(value)
+    }
+"#);
@@ -0,0 +1,488 @@
+//! Rust code of types and REST endpoints.
+//! Renders model before renaming, as generated code refers to Rust names of items and members.
+
+use extend::ext;
+
+use crate::prelude::*;
+use crate::model::*;
+use crate::model::log::*;
+use crate::model::rest::KParamLocation;
+
+mod server;
+mod client;
+pub use server::render_rust_server;
+pub use client::render_rust_client;
+
+/// Renders types in same form scanner reads.
+/// Scanning rendered code produces same model except spans.
+pub fn render_rust(x:&KMod) -> Result<String> {
+    let mut uses = vec!["use std::collections::{HashMap, HashSet};".to_string(), "use serde::{Serialize, Deserialize};".to_string()];
+    if x.uses_serde_repr() { uses.push("use serde_repr::{Serialize_repr, Deserialize_repr};".to_string()) }
+    let macros = ["RIDL", "rest", "int64"].into_iter().zip(x.uses_ridl_macros()).filter(|(_,x)| *x).map(|(x,_)| x).collect::<Vec<_>>();
+    match macros.as_slice() {
+        [] => (),
+        [x] => uses.push(format!("use ridl_derive::{};", x)),
+        xs => uses.push(format!("use ridl_derive::{{{}}};", xs.join(", "))),
+    }
+    let sections = vec![
+        render_doc_comment(&x.comment, "//!"),
+        uses.join("\n"),
+        x.items.iter().map_join(KItem::render, "\n\n")?,
+    ];
+    Ok(format!("{}\n", sections.into_iter().filter(|x| !x.is_empty()).collect::<Vec<_>>().join("\n\n")))
+}
+
+impl KMod {
+    fn uses_serde_repr(&self) -> bool {
+        self.items.iter().any(|x| match x {
+            KItem::Mod(x) => x.uses_serde_repr(),
+            KItem::Enum(x) => x.is_integer(),
+            _ => false,
+        })
+    }
+    /// Whether items use macros of `ridl_derive` to mark RIDL attributes. (`RIDL`, `rest` and `int64`)
+    /// Items which can derive use helper attributes of `RIDL` derive, and others use attribute macros.
+    fn uses_ridl_macros(&self) -> [bool; 3] {
+        let mut uses = [false; 3];
+        for x in self.items.iter() {
+            let attrs = match x {
+                KItem::Mod(x) => {
+                    for (a,b) in uses.iter_mut().zip(x.uses_ridl_macros()) { *a |= b }
+                    continue;
+                },
+                KItem::New(x) => vec![&x.attrs],
+                KItem::Func(x) => vec![&x.attrs],
+                KItem::Service(x) => std::iter::once(&x.attrs).chain(x.operations.iter().map(|x| &x.attrs)).collect(),
+                KItem::Nominal(x) => { uses[0] |= x.uses_ridl_derive(); continue },
+                KItem::Enum(x) => { uses[0] |= x.uses_ridl_derive(); continue },
+                KItem::Sum(x) => { uses[0] |= x.uses_ridl_derive(); continue },
+                KItem::Prod(x) => { uses[0] |= x.uses_ridl_derive(); continue },
+            };
+            uses[1] |= attrs.iter().any(|x| !x.rest.is_empty());
+            uses[2] |= attrs.iter().any(|x| x.int64.is_some());
+        }
+        uses
+    }
+}
+
+/// Items which can derive `RIDL` to mark their RIDL attributes.
+trait RIDLDerivable {
+    /// Whether the item or its members have RIDL attributes other than `serde`.
+    fn uses_ridl_derive(&self) -> bool;
+}
+
+impl RIDLDerivable for KNominalType {
+    fn uses_ridl_derive(&self) -> bool {
+        self.attrs.is_ridl()
+    }
+}
+
+impl RIDLDerivable for KEnumType {
+    fn uses_ridl_derive(&self) -> bool {
+        self.attrs.is_ridl() || self.cases.iter().any(|x| x.attrs.is_ridl())
+    }
+}
+
+impl RIDLDerivable for KSumType {
+    fn uses_ridl_derive(&self) -> bool {
+        self.attrs.is_ridl() || self.variants.iter().any(|x| x.attrs.is_ridl() || match &x.form {
+            KSumTypeVariantForm::Struct(xs) => xs.iter().any(|x| x.attrs.is_ridl()),
+            _ => false,
+        })
+    }
+}
+
+impl RIDLDerivable for KProdType {
+    fn uses_ridl_derive(&self) -> bool {
+        self.attrs.is_ridl() || self.fields.iter().any(|x| x.attrs.is_ridl())
+    }
+}
+
+trait RustRendering {
+    fn render(&self) -> Result<String>;
+}
+
+impl RustRendering for KItem {
+    fn render(&self) -> Result<String> {
+        use KItem::*;
+        match self {
+            Mod(x) => x.render(),
+            New(x) => x.render(),
+            Nominal(x) => x.render(),
+            Enum(x) => x.render(),
+            Sum(x) => x.render(),
+            Prod(x) => x.render(),
+            Func(x) => x.render(),
+            Service(x) => x.render(),
+        }
+    }
+}
+
+impl RustRendering for KMod {
+    /// Empty module has no import, as it would be unused.
+    fn render(&self) -> Result<String> {
+        let items = match self.items.is_empty() {
+            true => String::new(),
+            false => format!("use super::*;\n\n{}", self.items.iter().map_join(KItem::render, "\n\n")?),
+        };
+        Ok(render_item(&self.comment, Vec::new(), render_block(&format!("pub mod {}", self.name), &items)))
+    }
+}
+
+impl RustRendering for KNewType {
+    fn render(&self) -> Result<String> {
+        Ok(render_item(&self.comment, self.attrs.render(self.span)?, format!(
+            "pub type {name}{params} = {origin};",
+            name=self.name,
+            params=render_type_params(&self.type_params),
+            origin=self.origin.render(self.span)?,
+        )))
+    }
+}
+
+impl RustRendering for KNominalType {
+    fn render(&self) -> Result<String> {
+        let mut attrs = vec![DERIVE_SERDE.to_string()];
+        if self.uses_ridl_derive() { attrs.push(DERIVE_RIDL.to_string()) }
+        attrs.extend(self.attrs.render(self.span)?);
+        Ok(render_item(&self.comment, attrs, format!(
+            "pub struct {name}{params}(pub {origin});",
+            name=self.name,
+            params=render_type_params(&self.type_params),
+            origin=self.origin.render(self.span)?,
+        )))
+    }
+}
+
+impl RustRendering for KEnumType {
+    /// Integer-represented enum needs `serde_repr`, and renders all discriminants.
+    /// Cases have no data, so enums can always be keys of maps and sets.
+    fn render(&self) -> Result<String> {
+        let mut attrs = Vec::new();
+        match &self.repr {
+            None => attrs.push(DERIVE_SERDE.to_string()),
+            Some(x) => {
+                attrs.push("#[derive(Serialize_repr, Deserialize_repr)]".to_string());
+                attrs.push(format!("#[repr({})]", KType::Prim(x.clone()).render(self.span)?));
+            },
+        }
+        attrs.push("#[derive(Clone, Copy, PartialEq, Eq, Hash)]".to_string());
+        if self.uses_ridl_derive() { attrs.push(DERIVE_RIDL.to_string()) }
+        attrs.extend(self.attrs.render(self.span)?);
+        let cases = self.cases.iter().map_join(|x| Ok(render_item(&x.comment, x.attrs.render(x.span)?, match x.value {
+            None => format!("{},", x.name),
+            Some(v) => format!("{} = {},", x.name, v),
+        })), "\n")?;
+        Ok(render_item(&self.comment, attrs, render_block(&format!("pub enum {}", self.name), &cases)))
+    }
+}
+
+impl RustRendering for KSumType {
+    fn render(&self) -> Result<String> {
+        use KSumTypeSerializationForm::*;
+        let mut attrs = vec![DERIVE_SERDE.to_string()];
+        if self.uses_ridl_derive() { attrs.push(DERIVE_RIDL.to_string()) }
+        match &self.serialization {
+            NameBased => (),
+            TypeBased { discriminant } => attrs.push(format!("#[serde(tag={:?})]", discriminant)),
+            Adjacent { tag, content } => attrs.push(format!("#[serde(tag={:?}, content={:?})]", tag, content)),
+            Untagged => attrs.push("#[serde(untagged)]".to_string()),
+        }
+        attrs.extend(self.attrs.render(self.span)?);
+        let variants = self.variants.iter().map_join(KSumTypeVariant::render, "\n")?;
+        let head = format!("pub enum {}{}", self.name, render_type_params(&self.type_params));
+        Ok(render_item(&self.comment, attrs, render_block(&head, &variants)))
+    }
+}
+
+impl RustRendering for KSumTypeVariant {
+    fn render(&self) -> Result<String> {
+        use KSumTypeVariantForm::*;
+        let decl = match &self.form {
+            Unit => format!("{},", self.name),
+            Newtype => format!("{}({}),", self.name, self.content.render(self.span)?),
+            Tuple(xs) => format!("{}({}),", self.name, xs.iter().map_join(|x| x.render(self.span), ", ")?),
+            Struct(xs) => format!("{},", render_block(&self.name, &xs.iter().map_join(|x| x.render_field(""), "\n")?)),
+        };
+        Ok(render_item(&self.comment, self.attrs.render(self.span)?, decl))
+    }
+}
+
+impl RustRendering for KProdType {
+    fn render(&self) -> Result<String> {
+        let mut attrs = vec![DERIVE_SERDE.to_string()];
+        if self.uses_ridl_derive() { attrs.push(DERIVE_RIDL.to_string()) }
+        attrs.extend(self.attrs.render(self.span)?);
+        let fields = self.fields.iter().map_join(|x| x.render_field("pub "), "\n")?;
+        let head = format!("pub struct {}{}", self.name, render_type_params(&self.type_params));
+        Ok(render_item(&self.comment, attrs, render_block(&head, &fields)))
+    }
+}
+
+impl KProdTypeField {
+    fn render_field(&self, vis:&str) -> Result<String> {
+        Ok(render_item(&self.comment, self.attrs.render(self.span)?, format!(
+            "{vis}{name}: {content},",
+            vis=vis,
+            name=render_ident(&self.name, self.span)?,
+            content=self.content.render(self.span)?,
+        )))
+    }
+}
+
+impl RustRendering for KFuncType {
+    /// Output is always explicit as scanner requires it.
+    fn render(&self) -> Result<String> {
+        Ok(render_item(&self.comment, self.attrs.render(self.span)?, format!(
+            "pub type {name} = dyn Fn({input}) -> {output};",
+            name=self.name,
+            input=match self.input {
+                KType::Unit => String::new(),
+                ref x => x.render(self.span)?,
+            },
+            output=self.output.render(self.span)?,
+        )))
+    }
+}
+
+impl RustRendering for KService {
+    fn render(&self) -> Result<String> {
+        let operations = self.operations.iter().map_join(KOperation::render, "\n")?;
+        Ok(render_item(&self.comment, self.attrs.render(self.span)?, render_block(&format!("pub trait {}", self.name), &operations)))
+    }
+}
+
+impl RustRendering for KOperation {
+    fn render(&self) -> Result<String> {
+        Ok(render_item(&self.comment, self.attrs.render(self.span)?, format!(
+            "fn {name}(&self{input}){output};",
+            name=render_ident(&self.name, self.span)?,
+            input=match self.input {
+                KType::Unit => String::new(),
+                ref x => format!(", input: {}", x.render(self.span)?),
+            },
+            output=match self.output {
+                KType::Unit => String::new(),
+                ref x => format!(" -> {}", x.render(self.span)?),
+            },
+        )))
+    }
+}
+
+impl KAttrs {
+    fn is_ridl(&self) -> bool {
+        !self.rest.is_empty() || self.int64.is_some()
+    }
+    /// One attribute per line in stored order, so scanner reads them back in same order.
+    fn render(&self, span:KSpan) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        if let Some(x) = self.int64 {
+            lines.push(format!("#[int64({})]", match x {
+                KInt64Encoding::Number => "number",
+                KInt64Encoding::BigInt => "bigint",
+                KInt64Encoding::String => "string",
+            }));
+        }
+        for x in self.serde.iter() {
+            use KAttrSerde::*;
+            lines.push(match x {
+                Rename(x) => format!("#[serde(rename={:?})]", x),
+                RenameAll(x) => format!("#[serde(rename_all={:?})]", x.serde_name()),
+                Skip => "#[serde(skip)]".to_string(),
+                Default => "#[serde(default)]".to_string(),
+                SkipSerializingIf(x) => format!("#[serde(skip_serializing_if={:?})]", x),
+                With(x) => format!("#[serde(with={:?})]", x),
+                Tag(_) | Content(_) | Untagged => return err(span, "serialization form attribute is supported only on enum"),
+            });
+        }
+        for x in self.rest.iter() {
+            use KAttrREST::*;
+            lines.push(match x {
+                FnMethod(x) => match self.rest_path() {
+                    None => return err(span, "`#[rest(METHOD, \"/path\")]` needs a path"),
+                    Some(path) => format!("#[rest({}, {:?})]", x, path),
+                },
+                FnPath(_) => continue,
+                MessageIn => "#[rest(in)]".to_string(),
+                MessageOut => "#[rest(out)]".to_string(),
+                // Built-in `#[path]` attribute cannot be a helper of `RIDL` derive.
+                PathParam => "#[path_param]".to_string(),
+                QueryParam => "#[query]".to_string(),
+                HeaderParam(None) => "#[header]".to_string(),
+                HeaderParam(Some(x)) => format!("#[header({:?})]", x),
+                CookieParam(None) => "#[cookie]".to_string(),
+                CookieParam(Some(x)) => format!("#[cookie({:?})]", x),
+                BodyParam => "#[body]".to_string(),
+                Status(x) => format!("#[status({})]", x),
+                DefaultStatus => "#[status(default)]".to_string(),
+                MIME(x) => format!("#[mime({:?})]", x),
+            });
+        }
+        Ok(lines)
+    }
+}
+
+const DERIVE_SERDE: &str = "#[derive(Serialize, Deserialize)]";
+/// Helper attributes must follow the derive.
+const DERIVE_RIDL: &str = "#[derive(RIDL)]";
+
+/// Doc comment lines, then attributes, then the declaration.
+fn render_item(comment:&str, attrs:Vec<String>, decl:String) -> String {
+    let mut lines = Vec::new();
+    let comment = render_doc_comment(comment, "///");
+    if !comment.is_empty() { lines.push(comment) }
+    lines.extend(attrs);
+    lines.push(decl);
+    lines.join("\n")
+}
+/// Keeps empty lines, as scanner keeps them in comment.
+fn render_doc_comment(comment:&str, prefix:&str) -> String {
+    if comment.is_empty() { return String::new() }
+    comment.split('\n').map(|x| if x.is_empty() { prefix.to_string() } else { format!("{} {}", prefix, x) }).collect::<Vec<_>>().join("\n")
+}
+fn render_block(head:&str, body:&str) -> String {
+    if body.is_empty() { return format!("{} {{}}", head) }
+    format!("{} {{\n{}\n}}", head, body.to_string().indent())
+}
+fn render_type_params(xs:&[String]) -> String {
+    if xs.is_empty() { String::new() } else { format!("<{}>", xs.join(", ")) }
+}
+/// Keywords need raw identifier form. (e.g. `r#type`)
+fn render_ident(x:&str, span:KSpan) -> Result<String> {
+    if syn::parse_str::<syn::Ident>(x).is_ok() { return Ok(x.to_string()) }
+    let raw = format!("r#{}", x);
+    match syn::parse_str::<syn::Ident>(&raw) {
+        Ok(_) => Ok(raw),
+        Err(_) => err(span, &format!("`{}` is not a Rust identifier", x)),
+    }
+}
+
+#[ext(name=KTypeRustRendering)]
+impl KType {
+    fn render(&self, span:KSpan) -> Result<String> {
+        use KType::*;
+        use KPrimType::*;
+        let x = match self {
+            Vector(x) => return Ok(format!("Vec<{}>", x.render(span)?)),
+            Option(x) => return Ok(format!("Option<{}>", x.render(span)?)),
+            Map(k,v) => return Ok(format!("HashMap<{}, {}>", k.render(span)?, v.render(span)?)),
+            Set(x) => return Ok(format!("HashSet<{}>", x.render(span)?)),
+            Boxed(x) => return Ok(format!("Box<{}>", x.render(span)?)),
+            Def(x) => x,
+            App(x,args) => return Ok(format!("{}<{}>", x, args.iter().map_join(|x| x.render(span), ", ")?)),
+            Int64(x,_) => return Prim(x.clone()).render(span),
+            Prim(Bool) => "bool",
+            Prim(I8) => "i8",
+            Prim(I16) => "i16",
+            Prim(I32) => "i32",
+            Prim(I64) => "i64",
+            Prim(I128) => "i128",
+            Prim(ISize) => "isize",
+            Prim(U8) => "u8",
+            Prim(U16) => "u16",
+            Prim(U32) => "u32",
+            Prim(U64) => "u64",
+            Prim(U128) => "u128",
+            Prim(USize) => "usize",
+            Prim(F32) => "f32",
+            Prim(F64) => "f64",
+            Prim(Char) => "char",
+            Prim(String) => "String",
+            Unit => "()",
+            Never => "!",
+            Unknown => return err(span, "unsupported type pattern"),
+        };
+        Ok(x.to_string())
+    }
+    fn unwrap_option(&self) -> &KType {
+        match self {
+            KType::Option(x) => x,
+            _ => self,
+        }
+    }
+}
+
+impl KProdTypeField {
+    /// Name on wire of a `#[rest(in)]` field.
+    /// Names in `#[header("...")]` and `#[cookie("...")]` come first, then serialized name.
+    fn rest_wire_name<'a>(&'a self, serde_name:&'a str) -> &'a str {
+        match self.rest_location() {
+            Some(KParamLocation::Header(x)) | Some(KParamLocation::Cookie(x)) if x != self.name => x,
+            _ => serde_name,
+        }
+    }
+}
+
+#[ext(name=IterUtil)]
+impl<I:Iterator> I {
+    fn map_join<F:Fn(I::Item)->Result<String>>(&mut self, fx:F, sep:&str) -> Result<String> {
+        let mut oks = String::new();
+        let mut errs = PVec::new();
+        while let Some(x) = self.next() {
+            match fx(x) {
+                Ok(y) => oks.push_str(&y),
+                Err(y) => errs.extend(y.0),
+            }
+            oks.push_str(sep);
+        }
+        if !oks.is_empty() {
+            for _ in 0..sep.len() {
+                oks.pop();
+            }
+        }
+        if errs.is_empty() { Ok(oks) } else { Err(ErrorLogs(errs)) }
+    }
+}
+
+#[ext(name=StringUtil)]
+impl String {
+    fn commentize(&self) -> String {
+        let mut x = String::new();
+        let mut f = false;
+        for line in self.trim().lines() {
+            x.push_str("/// ");
+            x.push_str(line);
+            x.push_str("\n");
+            f = true;
+        }
+        if f { x.pop(); }
+        x
+    }
+    /// `PascalCase` item name to `snake_case` function name.
+    fn to_snake(&self) -> String {
+        let mut x = String::new();
+        for (i,c) in self.char_indices() {
+            if i > 0 && c.is_uppercase() { x.push('_') }
+            x.extend(c.to_lowercase());
+        }
+        x
+    }
+    fn indent(&self) -> String {
+        let mut x = String::new();
+        let mut f = false;
+        for line in self.lines() {
+            // Rust formatters reject trailing whitespaces.
+            if !line.is_empty() { x.push_str("    ") }
+            x.push_str(line);
+            x.push_str("\n");
+            f = true;
+        }
+        if f { x.pop(); }
+        x
+    }
+}
+
+#[ext(name=ResultUtil)]
+impl Result<String> {
+    fn trim(&self) -> Result<String> {
+        match self {
+            Err(x) => Err(x.clone()),
+            Ok(x) => Ok(x.trim().to_string()),
+        }
+    }
+}
+
+fn err<T>(span: KSpan, message: &str) -> Result<T> {
+    let log = Log { span: span, message: PString::new(message.to_string()) };
+    Err(ErrorLogs(PVec::from(vec![log])))
+}
@@ -0,0 +1,302 @@
+//! Server stubs of REST endpoints on `axum`.
+//! Uses the Rust types which the schema was scanned from.
+
+use indoc::indoc;
+
+use crate::prelude::*;
+use crate::model::rest::{self, KEndpoint, KParamLocation};
+use super::*;
+
+type Defs<'a> = Map<&'a str, &'a KItem>;
+
+pub fn render_rust_server(x:&KMod) -> Result<String> {
+    let mut defs = Map::new();
+    x.collect_defs(&mut defs);
+    let endpoints = x.named_endpoints(|x| x.to_string().to_snake(), &[])?;
+    let mut routes = Vec::<(&str, Vec<String>)>::new();
+    for (name,x) in endpoints.iter() {
+        let method = x.method.to_ascii_lowercase();
+        if !matches!(method.as_str(), "get" | "put" | "post" | "delete" | "options" | "head" | "patch" | "trace") {
+            return err(x.span, &format!("unsupported HTTP method `{}`", x.method))
+        }
+        let handler = format!("{}(handle_{}::<S>)", method, name);
+        match routes.iter_mut().find(|(path,_)| *path == x.path) {
+            Some((_,hs)) if hs.iter().any(|h| h.starts_with(&format!("{}(", method))) => return err(x.span, &format!("duplicate operation `{} {}`", x.method, x.path)),
+            Some((_,hs)) => hs.push(handler),
+            None => routes.push((x.path, vec![handler])),
+        }
+    }
+    // Each `#[rest(out)]` message is converted once even if shared by endpoints.
+    let mut messages_out = Vec::<&KSumType>::new();
+    for (_,x) in endpoints.iter() {
+        if let Some(x) = x.message_out(&defs) {
+            if !messages_out.iter().any(|y| y.name == x.name) { messages_out.push(x) }
+        }
+    }
+    Ok(format!(
+        indoc!(r#"
+            //! Server stubs of REST endpoints.
+            //! Place this as a child module of the module defining the types.
+
+            // Not all of runtime functions and imports are used by every schema.
+            #![allow(dead_code, unused_imports)]
+
+            use std::collections::HashMap;
+            use std::future::Future;
+            use std::sync::Arc;
+
+            use axum::Router;
+            use axum::body::Bytes;
+            use axum::extract::{{Path, Query, State}};
+            use axum::http::{{HeaderMap, StatusCode, header}};
+            use axum::response::{{IntoResponse, Response}};
+            use axum::routing;
+            use serde::Serialize;
+            use serde::de::DeserializeOwned;
+
+            use super::*;
+
+            /// Status of `#[status(default)]` variants, as they have no specific code.
+            pub const DEFAULT_STATUS: u16 = 500;
+
+            /// REST endpoints to implement.
+            /// Responses of `#[status(default)]` variants are sent with `DEFAULT_STATUS`.
+            pub trait Service: Send + Sync + 'static {{
+            {methods}
+            }}
+
+            /// Routes REST endpoints to `service`.
+            pub fn router<S: Service>(service: S) -> Router {{
+                Router::new()
+            {routes}
+                    .with_state(Arc::new(service))
+            }}
+
+            {handlers}
+
+            {responses}
+
+            {runtime}
+        "#),
+        methods=endpoints.iter().map_join(|(name,x)| x.render_rust_server_method(name), "\n")?.indent(),
+        routes=routes.iter().map(|(path,hs)| format!(r#".route("{}", routing::{})"#, path, hs.join("."))).collect::<Vec<_>>().join("\n").indent().indent(),
+        handlers=endpoints.iter().map_join(|(name,x)| x.render_rust_server_handler(name, &defs), "\n\n")?,
+        responses=messages_out.iter().map_join(|x| render_message_out_response(x), "\n\n")?,
+        runtime=RUNTIME.trim(),
+    ).replace("\n\n\n\n", "\n\n"))
+}
+
+#[ext(name=KEndpointRustServerRendering)]
+impl<'a> KEndpoint<'a> {
+    fn render_rust_server_method(&self, name:&str) -> Result<String> {
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                fn {name}(&self{input}) -> impl Future<Output = {output}> + Send;
+            "#),
+            comment=self.comment.to_string().commentize(),
+            name=name,
+            input=match self.input {
+                KType::Unit => String::new(),
+                x => format!(", input: {}", x.render(self.span)?),
+            },
+            output=self.output.render(self.span)?,
+        )).trim()
+    }
+    /// `#[rest(in)]` input is extracted from parameters and body.
+    /// `#[rest(out)]` output is converted by its `IntoResponse` implementation.
+    /// Any other input and output are JSON bodies.
+    fn render_rust_server_handler(&self, name:&str, defs:&Defs) -> Result<String> {
+        let mut extractors = vec!["State(service): State<Arc<S>>".to_string()];
+        let mut lines = Vec::new();
+        let (mut uses_path, mut uses_query, mut uses_headers) = (false, false, false);
+        match self.message_in(defs) {
+            Some(x) => {
+                // Optional bodies are alternatives. Request Content-Type selects one of them.
+                let bodies = x.rest_bodies();
+                if bodies.len() > 1 {
+                    uses_headers = true;
+                    lines.push(format!("let body_type = body_type(&headers, &body, &[{}])?;", bodies.iter().map(|(_,mime)| format!(r#""{}""#, mime)).collect::<Vec<_>>().join(", ")));
+                }
+                let mut fields = Vec::new();
+                for (f,wire) in x.fields.iter().zip(x.serde_field_names()) {
+                    let value = match f.rest_location().filter(|_| !f.attrs.has_serde_skip()) {
+                        // Not on wire. Scanner has checked that other fields have locations.
+                        None => "Default::default()".to_string(),
+                        Some(KParamLocation::Path) => {
+                            uses_path = true;
+                            let placeholder = rest::path_placeholders(self.path).unwrap_or_default().into_iter().find(|p| x.path_field(p).map(|y| y.name == f.name) == Some(true));
+                            let placeholder = match placeholder {
+                                None => return err(f.span, "`#[path]` field needs a placeholder in endpoint path"),
+                                Some(p) => p,
+                            };
+                            let value = format!(r#"parameter(required(path.get("{0}").map(String::as_str), "{0}")?)?"#, placeholder);
+                            if f.content.is_option() { format!("Some({})", value) } else { value }
+                        },
+                        Some(KParamLocation::Query) => {
+                            uses_query = true;
+                            match f.content.unwrap_option() {
+                                KType::Vector(_) | KType::Set(_) => {
+                                    let value = format!(r#"query_values(&query, "{}").map(parameter).collect::<Result<_, _>>()?"#, wire);
+                                    if f.content.is_option() { format!("Some({})", value) } else { value }
+                                },
+                                _ => render_parameter(f, &format!(r#"query_value(&query, "{}")"#, wire), &wire),
+                            }
+                        },
+                        Some(KParamLocation::Header(_)) => {
+                            uses_headers = true;
+                            let name = f.rest_wire_name(&wire);
+                            render_parameter(f, &format!(r#"header_value(&headers, "{}")"#, name), name)
+                        },
+                        Some(KParamLocation::Cookie(_)) => {
+                            uses_headers = true;
+                            let name = f.rest_wire_name(&wire);
+                            render_parameter(f, &format!(r#"cookie_value(&headers, "{}")"#, name), name)
+                        },
+                        Some(KParamLocation::Body(mime)) if bodies.len() > 1 => format!(r#"if body_type == Some("{0}") {{ Some(decode_body(&body, "{0}")?) }} else {{ None }}"#, mime),
+                        Some(KParamLocation::Body(mime)) => match f.is_omittable() {
+                            true => format!(r#"if body.is_empty() {{ Default::default() }} else {{ {}decode_body(&body, "{}")?{} }}"#, if f.content.is_option() { "Some(" } else { "" }, mime, if f.content.is_option() { ")" } else { "" }),
+                            false => format!(r#"decode_body(&body, "{}")?"#, mime),
+                        },
+                    };
+                    fields.push(format!("{}: {},", f.name, value));
+                }
+                lines.push(format!("let input = {} {{\n{}\n}};", x.name, fields.join("\n").indent()));
+            },
+            None if *self.input == KType::Unit => (),
+            None => lines.push(format!(r#"let input: {} = decode_body(&body, "application/json")?;"#, self.input.render(self.span)?)),
+        }
+        if uses_path { extractors.push("Path(path): Path<HashMap<String, String>>".to_string()) }
+        if uses_query { extractors.push("Query(query): Query<Vec<(String, String)>>".to_string()) }
+        if uses_headers { extractors.push("headers: HeaderMap".to_string()) }
+        if lines.iter().any(|x| x.contains("&body")) { extractors.push("body: Bytes".to_string()) }
+        let call = format!("service.{}({}).await", name, if *self.input == KType::Unit { "" } else { "input" });
+        lines.push(match (self.message_out(defs), self.output) {
+            (Some(_), _) => format!("Ok({}.into_response())", call),
+            (None, KType::Unit) => format!("{};\nOk(StatusCode::NO_CONTENT.into_response())", call),
+            (None, _) => format!("Ok(axum::Json({}).into_response())", call),
+        });
+        Ok(format!(
+            indoc!(r#"
+                async fn handle_{name}<S: Service>({extractors}) -> Result<Response, Rejection> {{
+                {lines}
+                }}
+            "#),
+            name=name,
+            extractors=extractors.join(", "),
+            lines=lines.join("\n").indent(),
+        )).trim()
+    }
+}
+
+/// Optional fields can be absent, and fields with `#[serde(default)]` fall back to default values.
+fn render_parameter(f:&KProdTypeField, value:&str, name:&str) -> String {
+    match (f.content.is_option(), f.is_omittable()) {
+        (true, _) => format!("{}.map(parameter).transpose()?", value),
+        (false, true) => format!("{}.map(parameter).transpose()?.unwrap_or_default()", value),
+        (false, false) => format!(r#"parameter(required({}, "{}")?)?"#, value, name),
+    }
+}
+
+/// Converts variants into their status codes and MIME-Types.
+/// `#[status(default)]` variants are sent with `DEFAULT_STATUS` as they have no specific code.
+fn render_message_out_response(x:&KSumType) -> Result<String> {
+    let mut arms = Vec::new();
+    for v in x.variants.iter() {
+        let status = match (v.attrs.rest_status(), v.attrs.rest_status_key()) {
+            (Some(x), _) => x.to_string(),
+            (None, Some(_)) => "DEFAULT_STATUS".to_string(),
+            (None, None) => return err(v.span, "missing status attribute"),
+        };
+        arms.push(match (&v.form, v.attrs.rest_mime()) {
+            (KSumTypeVariantForm::Unit, _) => format!("{}::{} => status_code({}).into_response(),", x.name, v.name, status),
+            (KSumTypeVariantForm::Newtype, Some(mime)) => format!(r#"{}::{}(x) => encode_response({}, "{}", &x),"#, x.name, v.name, status, mime),
+            (KSumTypeVariantForm::Newtype, None) => return err(v.span, "missing MIME-Type attribute"),
+            _ => return err(v.span, "Rust server supports only unit and new-type `#[rest(out)]` variants"),
+        });
+    }
+    Ok(format!(
+        indoc!(r#"
+            impl IntoResponse for {name} {{
+                fn into_response(self) -> Response {{
+                    match self {{
+            {arms}
+                    }}
+                }}
+            }}
+        "#),
+        name=x.name,
+        arms=arms.join("\n").indent().indent().indent(),
+    )).trim()
+}
+
+/// Extraction and codecs used by handlers.
+const RUNTIME: &str = indoc!(r#"
+    /// Response to a malformed request.
+    type Rejection = (StatusCode, String);
+
+    fn bad_request(message: impl std::fmt::Display) -> Rejection {
+        (StatusCode::BAD_REQUEST, message.to_string())
+    }
+    fn status_code(status: u16) -> StatusCode {
+        StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+    /// Selects the alternative body of request Content-Type. Empty body selects nothing.
+    fn body_type<'a>(headers: &HeaderMap, body: &[u8], content_types: &[&'a str]) -> Result<Option<&'a str>, Rejection> {
+        if body.is_empty() { return Ok(None) }
+        let content_type = header_value(headers, header::CONTENT_TYPE.as_str()).unwrap_or_default();
+        let content_type = content_type.split(';').next().unwrap_or_default().trim();
+        match content_types.iter().find(|x| x.eq_ignore_ascii_case(content_type)) {
+            Some(x) => Ok(Some(*x)),
+            None => Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, format!("unsupported Content-Type `{}`", content_type))),
+        }
+    }
+    fn required<'a>(x: Option<&'a str>, name: &str) -> Result<&'a str, Rejection> {
+        x.ok_or_else(|| bad_request(format!("missing parameter `{}`", name)))
+    }
+    /// Strings are received as is, and other values in JSON.
+    fn parameter<T: DeserializeOwned>(x: &str) -> Result<T, Rejection> {
+        serde_json::from_value(serde_json::Value::String(x.to_string()))
+            .or_else(|_| serde_json::from_str(x))
+            .map_err(bad_request)
+    }
+    fn query_value<'a>(query: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        query.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+    fn query_values<'a>(query: &'a [(String, String)], name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        query.iter().filter(move |(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+    fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+        headers.get(name).and_then(|x| x.to_str().ok())
+    }
+    fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+        headers.get_all(header::COOKIE).iter()
+            .filter_map(|x| x.to_str().ok())
+            .flat_map(|x| x.split(';'))
+            .find_map(|x| x.trim().strip_prefix(name)?.strip_prefix('='))
+    }
+    /// Bytes and non-JSON strings are received as is, and other values in JSON.
+    fn decode_body<T: DeserializeOwned>(body: &[u8], content_type: &str) -> Result<T, Rejection> {
+        if content_type.contains("json") { return serde_json::from_slice(body).map_err(bad_request) }
+        let value = match content_type.starts_with("text/") {
+            true => serde_json::Value::String(String::from_utf8(body.to_vec()).map_err(bad_request)?),
+            false => body.iter().map(|x| serde_json::Value::from(*x)).collect(),
+        };
+        serde_json::from_value(value).map_err(bad_request)
+    }
+    fn encode_body<T: Serialize>(x: &T, content_type: &str) -> serde_json::Result<Vec<u8>> {
+        let value = serde_json::to_value(x)?;
+        if !content_type.contains("json") {
+            if let serde_json::Value::String(x) = &value { return Ok(x.clone().into_bytes()) }
+            let bytes = value.as_array().and_then(|xs| xs.iter().map(|x| x.as_u64().and_then(|x| u8::try_from(x).ok())).collect::<Option<Vec<u8>>>());
+            if let Some(x) = bytes { return Ok(x) }
+        }
+        serde_json::to_vec(&value)
+    }
+    fn encode_response<T: Serialize>(status: u16, content_type: &'static str, x: &T) -> Response {
+        match encode_body(x, content_type) {
+            Ok(body) => (status_code(status), [(header::CONTENT_TYPE, content_type)], body).into_response(),
+            Err(x) => (StatusCode::INTERNAL_SERVER_ERROR, x.to_string()).into_response(),
+        }
+    }
+"#);
@@ -0,0 +1,256 @@
+//! HTTP client of REST endpoints.
+//! Uses types rendered by `render_swift5`.
+
+use indoc::indoc;
+
+use crate::prelude::*;
+use crate::model::rest::{self, KEndpoint, KParamLocation};
+use super::*;
+
+type Defs<'a> = Map<&'a str, &'a KItem>;
+
+pub fn render_swift5_client(x:&KMod) -> Result<String> {
+    let mut defs = Map::new();
+    x.collect_defs(&mut defs);
+    let endpoints = x.named_endpoints(|x| x.to_string().to_lower_camel(), &[])?;
+    Ok(format!(
+        indoc!(r#"
+            import Foundation
+
+            /// HTTP client of REST endpoints.
+            struct Client {{
+                var baseURL: URL
+                var transport: HTTPTransport
+                init(baseURL: URL, transport: HTTPTransport = URLSessionTransport()) {{
+                    self.baseURL = baseURL
+                    self.transport = transport
+                }}
+            {methods}
+            }}
+
+            {runtime}
+        "#),
+        methods=endpoints.iter().map_join(|(name,x)| x.render_swift5_client(name, &defs), "\n")?.indent(),
+        runtime=RUNTIME.trim(),
+    ))
+}
+
+#[ext(name=KEndpointSwift5ClientRendering)]
+impl<'a> KEndpoint<'a> {
+    /// `#[rest(in)]` input is sent as parameters and body.
+    /// `#[rest(out)]` output is decoded by status code.
+    /// Any other input and output are JSON bodies.
+    fn render_swift5_client(&self, name:&str, defs:&Defs) -> Result<String> {
+        let message_in = self.message_in(defs);
+        let escaped_path = self.path.replace('\\', "\\\\").replace('"', "\\\"");
+        let path = rest::replace_placeholders(&escaped_path, |name| match message_in.and_then(|x| x.path_field(name)) {
+            None => err(self.span, &format!("path placeholder `{{{}}}` needs a `#[path]` field of `#[rest(in)]` input", name)),
+            Some(x) => Ok(format!(r#"\(try pathValue(input.{}))"#, render_ident(&x.name))),
+        })?;
+        let mut queries = Vec::new();
+        let mut settings = Vec::new();
+        let mut cookies = Vec::new();
+        let mut bodies = Vec::new();
+        match message_in {
+            Some(x) => {
+                for (f,location) in x.rest_params() {
+                    match location {
+                        KParamLocation::Path => (),
+                        KParamLocation::Query => queries.push(render_optional_binding(f, |x| match f.content.unwrap_option() {
+                            KType::Vector(_) | KType::Set(_) => format!(r#"queryItems += try {}.map {{ URLQueryItem(name: {}, value: try parameterValue($0)) }}"#, x, render_string(&f.name)),
+                            _ => format!(r#"queryItems.append(URLQueryItem(name: {}, value: try parameterValue({})))"#, render_string(&f.name), x),
+                        })),
+                        KParamLocation::Header(name) => settings.push(render_optional_binding(f, |x| format!(r#"request.setValue(try parameterValue({}), forHTTPHeaderField: {})"#, x, render_string(name)))),
+                        KParamLocation::Cookie(name) => cookies.push(render_optional_binding(f, |x| format!(r#"cookies.append({} + (try parameterValue({})))"#, render_string(&format!("{}=", name)), x))),
+                        KParamLocation::Body(mime) => bodies.push(render_optional_binding(f, |x| format!(
+                            indoc!(r#"
+                                request.setValue({mime}, forHTTPHeaderField: "Content-Type")
+                                request.httpBody = try encodeBody({x}, contentType: {mime})
+                            "#),
+                            mime=render_string(mime),
+                            x=x,
+                        ).trim().to_string())),
+                    }
+                }
+                // Optional bodies are alternatives. The first present one is sent.
+                if !bodies.is_empty() { settings.push(bodies.join(" else ")) }
+            },
+            None if *self.input == KType::Unit => (),
+            None => settings.push(indoc!(r#"
+                request.setValue("application/json", forHTTPHeaderField: "Content-Type")
+                request.httpBody = try JSONEncoder().encode(input)
+            "#).trim().to_string()),
+        }
+        if !cookies.is_empty() {
+            settings.push(format!(
+                indoc!(r#"
+                    var cookies = [String]()
+                    {cookies}
+                    if !cookies.isEmpty {{ request.setValue(cookies.joined(separator: "; "), forHTTPHeaderField: "Cookie") }}
+                "#),
+                cookies=cookies.join("\n"),
+            ).trim().to_string());
+        }
+        let mut lines = Vec::new();
+        if !queries.is_empty() {
+            lines.push("var queryItems = [URLQueryItem]()".to_string());
+            lines.extend(queries.iter().cloned());
+        }
+        lines.push(format!(
+            r#"{binding} request = try makeRequest(method: "{method}", path: "{path}", queryItems: {queries})"#,
+            binding=if settings.is_empty() { "let" } else { "var" },
+            method=self.method,
+            path=path,
+            queries=if queries.is_empty() { "[]" } else { "queryItems" },
+        ));
+        lines.extend(settings);
+        lines.push("let (data, response) = try await transport.send(request)".to_string());
+        lines.push(match self.message_out(defs) {
+            Some(x) => render_message_out_decoding(x)?,
+            None => render_default_decoding(self.output, self.span)?,
+        });
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                func {name}({input}) async throws{output} {{
+                {lines}
+                }}
+            "#),
+            comment=self.comment.to_string().commentize(),
+            name=name,
+            input=match self.input {
+                KType::Unit => String::new(),
+                x => format!("_ input: {}", x.render(self.span)?),
+            },
+            output=match self.output {
+                KType::Unit => String::new(),
+                x => format!(" -> {}", x.render(self.span)?),
+            },
+            lines=lines.join("\n").indent(),
+        )).trim()
+    }
+}
+
+/// Renders statements on the field, or on its unwrapped value only if it exists.
+fn render_optional_binding(f:&KProdTypeField, render:impl Fn(&str) -> String) -> String {
+    if !f.is_swift_optional() { return render(&format!("input.{}", render_ident(&f.name))) }
+    format!(
+        indoc!(r#"
+            if let x = input.{name} {{
+            {code}
+            }}
+        "#),
+        name=render_ident(&f.name),
+        code=render("x").indent(),
+    ).trim().to_string()
+}
+
+/// Any 2xx status is a success.
+fn render_default_decoding(output:&KType, span:KSpan) -> Result<String> {
+    let guard = "guard (200..<300).contains(response.statusCode) else { throw HTTPStatusError(status: response.statusCode, body: data) }";
+    match output {
+        KType::Unit => Ok(guard.to_string()),
+        x => Ok(format!("{}\nreturn try JSONDecoder().decode({}.self, from: data)", guard, x.render(span)?)),
+    }
+}
+
+/// Picks variant by status code, and then by MIME-Type if a status has multiple variants.
+/// `#[status(default)]` variants are tried last.
+fn render_message_out_decoding(x:&KSumType) -> Result<String> {
+    let (cases, is_exhaustive) = x.rest_response_cases()?;
+    let mut lines = Vec::new();
+    for case in cases.iter() {
+        let v = case.variant;
+        let value = match case.mime {
+            None => format!(".{}", render_ident(&v.name)),
+            Some(_) => format!(".{}(try decodeBody({}.self, from: data, contentType: contentType))", render_ident(&v.name), v.content.render(v.span)?),
+        };
+        let guard = case.content_type.map_or(String::new(), |x| format!(" where contentType.hasPrefix({})", render_string(x)));
+        let pattern = match (case.status, guard.is_empty()) {
+            (None, true) => "default".to_string(),
+            (None, false) => format!("case _{}", guard),
+            (Some(x), _) => format!("case {}{}", x, guard),
+        };
+        lines.push(format!("{}: return {}", pattern, value));
+    }
+    if !is_exhaustive { lines.push("default: throw HTTPStatusError(status: response.statusCode, body: data)".to_string()) }
+    let content_type = if cases.iter().any(|x| x.mime.is_some()) {
+        "let contentType = response.value(forHTTPHeaderField: \"Content-Type\") ?? \"\"\n"
+    } else {
+        ""
+    };
+    Ok(format!(
+        indoc!(r#"
+            {content_type}switch response.statusCode {{
+            {cases}
+            }}
+        "#),
+        content_type=content_type,
+        cases=lines.join("\n"),
+    ).trim().to_string())
+}
+
+/// Transport and codecs used by `Client`.
+const RUNTIME: &str = indoc!(r#"
+    /// Sends HTTP requests.
+    /// Inject a fake to test without network.
+    protocol HTTPTransport {
+        func send(_ request: URLRequest) async throws -> (Data, HTTPURLResponse)
+    }
+
+    /// Sends HTTP requests with `URLSession`.
+    struct URLSessionTransport: HTTPTransport {
+        var session: URLSession = .shared
+        func send(_ request: URLRequest) async throws -> (Data, HTTPURLResponse) {
+            let (data, response) = try await session.data(for: request)
+            guard let x = response as? HTTPURLResponse else { throw URLError(.badServerResponse) }
+            return (data, x)
+        }
+    }
+
+    /// Response with a status code not declared by endpoint.
+    struct HTTPStatusError: Error {
+        var status: Int
+        var body: Data
+    }
+
+    extension Client {
+        private func makeRequest(method: String, path: String, queryItems: [URLQueryItem]) throws -> URLRequest {
+            guard var components = URLComponents(url: baseURL, resolvingAgainstBaseURL: false) else { throw URLError(.badURL) }
+            if components.percentEncodedPath.hasSuffix("/") { components.percentEncodedPath.removeLast() }
+            components.percentEncodedPath += path
+            if !queryItems.isEmpty { components.queryItems = queryItems }
+            guard let url = components.url else { throw URLError(.badURL) }
+            var request = URLRequest(url: url)
+            request.httpMethod = method
+            return request
+        }
+    }
+
+    /// Strings are sent as is, and other values in JSON.
+    fileprivate func parameterValue<T: Encodable>(_ value: T) throws -> String {
+        let data = try JSONEncoder().encode(value)
+        if let x = try? JSONDecoder().decode(String.self, from: data) { return x }
+        return String(decoding: data, as: UTF8.self)
+    }
+    fileprivate func pathValue<T: Encodable>(_ value: T) throws -> String {
+        let x = try parameterValue(value)
+        let allowed = CharacterSet.urlPathAllowed.subtracting(CharacterSet(charactersIn: "/"))
+        return x.addingPercentEncoding(withAllowedCharacters: allowed) ?? x
+    }
+    /// Bytes and non-JSON strings are sent as is, and other values in JSON.
+    fileprivate func encodeBody<T: Encodable>(_ value: T, contentType: String) throws -> Data {
+        switch value {
+        case let x as Data: return x
+        case let x as [UInt8]: return Data(x)
+        case let x as String where !contentType.contains("json"): return Data(x.utf8)
+        default: return try JSONEncoder().encode(value)
+        }
+    }
+    fileprivate func decodeBody<T: Decodable>(_ type: T.Type, from data: Data, contentType: String) throws -> T {
+        if let x = data as? T { return x }
+        if let x = [UInt8](data) as? T { return x }
+        if !contentType.contains("json"), let x = String(decoding: data, as: UTF8.self) as? T { return x }
+        return try JSONDecoder().decode(T.self, from: data)
+    }
+"#);
@@ -4,11 +4,119 @@ use indoc::indoc;
 use crate::prelude::*;
 use crate::model::*;
 use crate::model::log::*;
+use crate::model::recursion::KStorage;
+
+mod client;
+pub use client::render_swift5_client;
 
 pub fn render_swift5(x:&KMod) -> Result<String> {
-    x.render()
+    let mut x = x.clone();
+    x.box_recursive_members();
+    let mut code = x.render()?;
+    if x.uses_decimal_string() {
+        code = format!("{}\n\n", code.trim_end());
+        code.push_str(DECIMAL_STRING);
+    }
+    if x.uses_indirect() {
+        code = format!("{}\n\n", code.trim_end());
+        code.push_str(INDIRECT);
+    }
+    Ok(code)
 }
 
+impl KMod {
+    /// Swift value-types cannot store themselves.
+    /// Boxes members which store a definition in same recursion cycle without heap-allocated container.
+    /// Boxed sum-type variants become `indirect` cases, and boxed prod-type fields and nominal-type values become `@Indirect` properties.
+    /// Type aliases are expanded into the types which use them, so cycles through aliases are boxed there.
+    fn box_recursive_members(&mut self) {
+        let is_inline = |x:KStorage| x != KStorage::Heap;
+        let cycles = self.find_cycles(is_inline).iter().map(|x| x.iter().map(|x| x.to_string()).collect::<Vec<_>>()).collect::<Vec<_>>();
+        for cycle in cycles.iter() {
+            let names = cycle.iter().map(String::as_str).collect::<Vec<_>>();
+            self.box_members(&names, &is_inline);
+        }
+    }
+    fn box_members(&mut self, names:&[&str], is_inline:&impl Fn(KStorage) -> bool) {
+        let box_if_needed = |x:&mut KType| if !x.is_boxed() && x.refers_inline(names, is_inline) {
+            *x = KType::Boxed(Box::new(std::mem::take(x)));
+        };
+        for item in self.items.iter_mut() {
+            match item {
+                KItem::Mod(x) => x.box_members(names, is_inline),
+                KItem::Sum(x) if names.contains(&x.name.as_str()) => for v in x.variants.iter_mut() { v.for_each_type_mut(&mut |x| box_if_needed(x)) },
+                KItem::Prod(x) if names.contains(&x.name.as_str()) => for f in x.fields.iter_mut() { box_if_needed(&mut f.content) },
+                KItem::Nominal(x) if names.contains(&x.name.as_str()) => box_if_needed(&mut x.origin),
+                _ => (),
+            }
+        }
+    }
+    fn uses_indirect(&self) -> bool {
+        self.items.iter().any(|x| match x {
+            KItem::Mod(x) => x.uses_indirect(),
+            KItem::Prod(x) => x.fields.iter().any(|x| x.content.is_boxed()),
+            KItem::Nominal(x) => x.origin.is_boxed(),
+            _ => false,
+        })
+    }
+    fn uses_decimal_string(&self) -> bool {
+        let mut found = false;
+        for item in self.items.iter() {
+            item.for_each_type(&mut |x| found |= x.any(&|x| matches!(x, KType::Int64(_,KInt64Encoding::String))));
+        }
+        found
+    }
+}
+/// Codec for integers encoded as decimal strings.
+const DECIMAL_STRING: &str = indoc!(r#"
+    /// An integer encoded as a decimal string.
+    struct DecimalString<Value: FixedWidthInteger & Codable>: Hashable, Codable {
+        var value: Value
+        init(_ value: Value) {
+            self.value = value
+        }
+        init(from decoder: Decoder) throws {
+            let container = try decoder.singleValueContainer()
+            let text = try container.decode(String.self)
+            guard let x = Value(text) else { throw DecodingError.dataCorruptedError(in: container, debugDescription: "bad integer string `\(text)`") }
+            value = x
+        }
+        func encode(to encoder: Encoder) throws {
+            var container = encoder.singleValueContainer()
+            try container.encode(String(value))
+        }
+    }
+"#);
+/// Property wrapper for boxed prod-type fields.
+const INDIRECT: &str = indoc!(r#"
+    /// Stores a value in heap to break recursion of value-types.
+    @propertyWrapper
+    enum Indirect<Value: Hashable & Codable>: Hashable, Codable {
+        indirect case value(Value)
+        init(wrappedValue: Value) {
+            self = .value(wrappedValue)
+        }
+        var wrappedValue: Value {
+            get { switch self { case let .value(x): return x } }
+            set { self = .value(newValue) }
+        }
+        init(from decoder: Decoder) throws {
+            let container = try decoder.singleValueContainer()
+            self = .value(try container.decode(Value.self))
+        }
+        func encode(to encoder: Encoder) throws {
+            var container = encoder.singleValueContainer()
+            try container.encode(wrappedValue)
+        }
+    }
+    extension KeyedDecodingContainer {
+        /// Missing optional fields decode to `nil` like non-wrapped ones.
+        func decode<T: Hashable & Codable>(_ type: Indirect<T?>.Type, forKey key: Key) throws -> Indirect<T?> {
+            Indirect(wrappedValue: try decodeIfPresent(T.self, forKey: key))
+        }
+    }
+"#);
+
 trait Swift5Rendering {
     fn render(&self) -> Result<String>;
 }
@@ -35,10 +143,12 @@ impl Swift5Rendering for KItem {
         match self {
             Mod(x) => x.render(),
             New(x) => x.render(),
+            Nominal(x) => x.render(),
             Enum(x) => x.render(),
             Sum(x) => x.render(),
             Prod(x) => x.render(),
             Func(x) => x.render(),
+            Service(x) => x.render(),
         }.trim()
     }
 }
@@ -48,10 +158,41 @@ impl Swift5Rendering for KNewType {
         Ok(format!(
             indoc!(r#"
                 {comment}   
-                typealias {name} = {origin}
+                typealias {name}{params} = {origin}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
+            params=render_type_params(&self.type_params, ""),
+            origin=self.origin.render(self.span)?,
+        ))
+    }
+}
+
+impl Swift5Rendering for KNominalType {
+    /// Encoded as a single value like `serde` does for tuple structs.
+    fn render(&self) -> Result<String> {
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                struct {name}{params}: RawRepresentable, Hashable, Codable {{
+                    {wrapper}var rawValue: {origin}
+                    init(rawValue: {origin}) {{
+                        self.rawValue = rawValue
+                    }}
+                    init(from decoder: Decoder) throws {{
+                        let container = try decoder.singleValueContainer()
+                        rawValue = try container.decode({origin}.self)
+                    }}
+                    func encode(to encoder: Encoder) throws {{
+                        var container = encoder.singleValueContainer()
+                        try container.encode(rawValue)
+                    }}
+                }}
+            "#),
+            comment=self.comment.commentize(),
+            name=self.name,
+            params=render_type_params(&self.type_params, CODABLE_BOUND),
+            wrapper=if self.origin.is_boxed() { "@Indirect " } else { "" },
             origin=self.origin.render(self.span)?,
         ))
     }
@@ -62,59 +203,414 @@ impl Swift5Rendering for KEnumType {
         Ok(format!(
             indoc!(r#"
                 {comment}
-                enum {name}: String, Equatable, Codable {{
+                enum {name}: {raw}, Hashable, Codable {{
                 {cases}
                 }}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
-            cases=self.cases.iter().map_join(render_case, "\n")?.indent(),
+            raw=if self.is_integer() { "Int" } else { "String" },
+            cases=self.cases.iter().map_join(|x| render_case(x, self.is_integer()), "\n")?.indent(),
         ))
     }
 }
-fn render_case(x:&KEnumTypeCase) -> Result<String> {
-    Ok(format!(r#"case {name} = "{value}""#, name=x.name, value=x.name))
+fn render_case(x:&KEnumTypeCase, is_integer:bool) -> Result<String> {
+    match (is_integer, x.value) {
+        (true, Some(value)) => Ok(format!("case {name} = {value}", name=render_ident(&x.name), value=value)),
+        (true, None) => err(x.span, "missing discriminant of integer-represented enum case"),
+        (false, _) => Ok(format!("case {name} = {value}", name=render_ident(&x.name), value=render_string(&x.name))),
+    }
 }
 
 impl Swift5Rendering for KSumType {
     fn render(&self) -> Result<String> {
+        use KSumTypeSerializationForm::*;
+        let codable = match &self.serialization {
+            NameBased => format!("\n{}", self.render_name_based_codable()?.indent()),
+            TypeBased { discriminant } => format!("\n{}", self.render_type_based_codable(discriminant)?.indent()),
+            Adjacent { tag, content } => format!("\n{}", self.render_adjacent_codable(tag, content)?.indent()),
+            Untagged => format!("\n{}", self.render_untagged_codable()?.indent()),
+        };
+        let payload_decoders: String = self.variants.iter().filter_map(render_payload_decoder).collect::<Result<Vec<_>>>()?.iter().map(|x| format!("\n{}", x.indent())).collect();
         Ok(format!(
             indoc!(r#"
                 {comment}
-                enum {name}: Equatable, Codable {{
-                {variants}
+                enum {name}{params}: Hashable, Codable {{
+                {variants}{codable}{payload_decoders}
                 }}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
+            params=render_type_params(&self.type_params, CODABLE_BOUND),
             variants=self.variants.iter().map_join(render_sum_type_variant, "\n")?.indent(),
+            codable=codable,
+            payload_decoders=payload_decoders,
         ))
     }
 }
+impl KSumType {
+    /// Externally tagged form with non-new-type variants.
+    /// Unit variants are bare strings, and others are objects with single property named by variant.
+    fn render_name_based_codable(&self) -> Result<String> {
+        let units = self.variants.iter().filter(|x| x.form == KSumTypeVariantForm::Unit).collect::<Vec<_>>();
+        let others = self.variants.iter().filter(|x| x.form != KSumTypeVariantForm::Unit).collect::<Vec<_>>();
+        let keys = others.iter().map(|x| render_coding_key(&x.name)).collect::<Vec<_>>().join("\n");
+        let unit_decodes = units.iter().map(|x| format!("case {value}: self = .{name}; return", value=render_string(&x.name), name=render_ident(&x.name))).collect::<Vec<_>>().join("\n");
+        let decodes = others.iter().map_join(|x| Ok(format!(
+            "case .{name}: {payload}",
+            name=render_ident(&x.name),
+            payload=render_payload_decode(x, "payload")?,
+        )), "\n")?;
+        let encodes = self.variants.iter().map_join(|x| match &x.form {
+            KSumTypeVariantForm::Unit => Ok(format!(
+                indoc!(r#"
+                    case .{name}:
+                        var container = encoder.singleValueContainer()
+                        try container.encode({value})
+                "#),
+                name=render_ident(&x.name),
+                value=render_string(&x.name),
+            )).trim(),
+            _ => Ok(format!(
+                indoc!(r#"
+                    {pattern}
+                        var container = encoder.container(keyedBy: VariantKeys.self)
+                    {payload}
+                "#),
+                pattern=render_payload_pattern(x),
+                payload=render_payload_encode(x, &format!("container.superEncoder(forKey: .{})", render_ident(&x.name)))?.indent(),
+            )).trim(),
+        }, "\n")?;
+        let unit_decoding = if units.is_empty() { String::new() } else { format!("{}\n", format!(
+            indoc!(r#"
+                if let container = try? decoder.singleValueContainer(), let x = try? container.decode(String.self) {{
+                    switch x {{
+                {unit_decodes}
+                    default: throw DecodingError.dataCorruptedError(in: container, debugDescription: "unknown variant `\(x)`")
+                    }}
+                }}
+            "#),
+            unit_decodes=unit_decodes.indent(),
+        ).trim_end().to_string().indent())};
+        Ok(format!(
+            indoc!(r#"
+                private enum VariantKeys: String, CodingKey {{
+                {keys}
+                }}
+                init(from decoder: Decoder) throws {{
+                {unit_decoding}    let container = try decoder.container(keyedBy: VariantKeys.self)
+                    guard container.allKeys.count == 1, let key = container.allKeys.first else {{
+                        throw DecodingError.dataCorrupted(DecodingError.Context(codingPath: container.codingPath, debugDescription: "expected single variant of enum `{name}`"))
+                    }}
+                    let payload = try container.superDecoder(forKey: key)
+                    switch key {{
+                {decodes}
+                    }}
+                }}
+                func encode(to encoder: Encoder) throws {{
+                    switch self {{
+                {encodes}
+                    }}
+                }}
+            "#),
+            name=self.name,
+            keys=keys.indent(),
+            unit_decoding=unit_decoding,
+            decodes=decodes.indent(),
+            encodes=encodes.indent(),
+        )).trim()
+    }
+    /// Internally tagged form.
+    /// Reads tag first, and decodes content from same container.
+    fn render_type_based_codable(&self, discriminant:&str) -> Result<String> {
+        let decodes = self.variants.iter().map_join(|x| Ok(format!(
+            "case {value}: {payload}",
+            value=render_string(&x.name),
+            payload=render_payload_decode(x, "decoder")?,
+        )), "\n")?;
+        let encodes = self.variants.iter().map_join(|x| Ok(format!(
+            indoc!(r#"
+                {pattern}
+                    try container.encode({value}, forKey: .tag)
+                {payload}
+            "#),
+            pattern=render_payload_pattern(x),
+            value=render_string(&x.name),
+            payload=render_payload_encode(x, "encoder")?.indent(),
+        )).trim(), "\n")?;
+        Ok(format!(
+            indoc!(r#"
+                private enum CodingKeys: String, CodingKey {{
+                    case tag = {discriminant}
+                }}
+                init(from decoder: Decoder) throws {{
+                    let container = try decoder.container(keyedBy: CodingKeys.self)
+                    switch try container.decode(String.self, forKey: .tag) {{
+                {decodes}
+                    case let x: throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "unknown variant `\(x)`")
+                    }}
+                }}
+                func encode(to encoder: Encoder) throws {{
+                    var container = encoder.container(keyedBy: CodingKeys.self)
+                    switch self {{
+                {encodes}
+                    }}
+                }}
+            "#),
+            discriminant=render_string(discriminant),
+            decodes=decodes.indent(),
+            encodes=encodes.indent(),
+        )).trim()
+    }
+    /// Adjacently tagged form.
+    /// Reads tag first, and decodes content from content property.
+    /// Unit variants have no content property.
+    fn render_adjacent_codable(&self, tag:&str, content:&str) -> Result<String> {
+        let decodes = self.variants.iter().map_join(|x| Ok(match &x.form {
+            KSumTypeVariantForm::Newtype => format!(
+                "case {value}: self = .{name}(try container.decode({ty}.self, forKey: .content))",
+                value=render_string(&x.name),
+                name=render_ident(&x.name),
+                ty=x.content.render(x.span)?,
+            ),
+            _ => format!(
+                "case {value}: {payload}",
+                value=render_string(&x.name),
+                payload=render_payload_decode(x, "container.superDecoder(forKey: .content)")?,
+            ),
+        }), "\n")?;
+        let encodes = self.variants.iter().map_join(|x| Ok(format!(
+            indoc!(r#"
+                {pattern}
+                    try container.encode({value}, forKey: .tag)
+                {payload}
+            "#),
+            pattern=render_payload_pattern(x),
+            value=render_string(&x.name),
+            payload=match &x.form {
+                KSumTypeVariantForm::Newtype => "try container.encode(x, forKey: .content)".to_string(),
+                _ => render_payload_encode(x, "container.superEncoder(forKey: .content)")?,
+            }.indent(),
+        )).trim(), "\n")?;
+        Ok(format!(
+            indoc!(r#"
+                private enum CodingKeys: String, CodingKey {{
+                    case tag = {tag}
+                    case content = {content}
+                }}
+                init(from decoder: Decoder) throws {{
+                    let container = try decoder.container(keyedBy: CodingKeys.self)
+                    switch try container.decode(String.self, forKey: .tag) {{
+                {decodes}
+                    case let x: throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "unknown variant `\(x)`")
+                    }}
+                }}
+                func encode(to encoder: Encoder) throws {{
+                    var container = encoder.container(keyedBy: CodingKeys.self)
+                    switch self {{
+                {encodes}
+                    }}
+                }}
+            "#),
+            tag=render_string(tag),
+            content=render_string(content),
+            decodes=decodes.indent(),
+            encodes=encodes.indent(),
+        )).trim()
+    }
+    /// Untagged form.
+    /// Tries variants in order like `serde` does.
+    /// Unit variants are `null`.
+    fn render_untagged_codable(&self) -> Result<String> {
+        let decodes = self.variants.iter().map_join(|x| Ok(match &x.form {
+            KSumTypeVariantForm::Newtype => format!(
+                r#"do {{ let x: {ty} = try .init(from: decoder); self = .{name}(x); return }} catch {{}}"#,
+                name=render_ident(&x.name),
+                ty=x.content.render(x.span)?,
+            ),
+            KSumTypeVariantForm::Unit => format!(r#"if container.decodeNil() {{ self = .{name}; return }}"#, name=render_ident(&x.name)),
+            _ => format!(r#"if let x = try? Self.decode{name}(from: decoder) {{ self = x; return }}"#, name=to_ident(&x.name).to_upper_camel()),
+        }), "\n")?;
+        // Tuple and struct variants need their own containers, so single value container cannot be shared.
+        let is_single_value = self.variants.iter().all(|x| matches!(x.form, KSumTypeVariantForm::Newtype | KSumTypeVariantForm::Unit));
+        let encodes = self.variants.iter().map_join(|x| Ok(match (&x.form, is_single_value) {
+            (KSumTypeVariantForm::Newtype, true) => format!(r#"case let .{name}(x): try container.encode(x)"#, name=render_ident(&x.name)),
+            (KSumTypeVariantForm::Unit, true) => format!(r#"case .{name}: try container.encodeNil()"#, name=render_ident(&x.name)),
+            (KSumTypeVariantForm::Unit, false) => format!(
+                indoc!(r#"
+                    case .{name}:
+                        var container = encoder.singleValueContainer()
+                        try container.encodeNil()
+                "#),
+                name=render_ident(&x.name),
+            ).trim().to_string(),
+            _ => format!(
+                indoc!(r#"
+                    {pattern}
+                    {payload}
+                "#),
+                pattern=render_payload_pattern(x),
+                payload=render_payload_encode(x, "encoder")?.indent(),
+            ).trim().to_string(),
+        }), "\n")?;
+        Ok(format!(
+            indoc!(r#"
+                init(from decoder: Decoder) throws {{
+                    let container = try decoder.singleValueContainer()
+                {decodes}
+                    throw DecodingError.dataCorruptedError(in: container, debugDescription: "data did not match any variant of untagged enum `{name}`")
+                }}
+                func encode(to encoder: Encoder) throws {{
+                {container}    switch self {{
+                {encodes}
+                    }}
+                }}
+            "#),
+            name=self.name,
+            decodes=decodes.indent(),
+            container=if is_single_value { "    var container = encoder.singleValueContainer()\n" } else { "" },
+            encodes=encodes.indent(),
+        )).trim()
+    }
+}
 fn render_sum_type_variant(x:&KSumTypeVariant) -> Result<String> {
+    let mut is_boxed = false;
+    x.for_each_type(&mut |x| is_boxed |= x.is_boxed());
+    let payload = match &x.form {
+        KSumTypeVariantForm::Newtype => format!("({})", x.content.render(x.span)?),
+        KSumTypeVariantForm::Unit => String::new(),
+        KSumTypeVariantForm::Tuple(xs) => format!("({})", xs.iter().map_join(|t| t.render(x.span), ", ")?),
+        KSumTypeVariantForm::Struct(fs) => format!("({})", fs.iter().map_join(|f| Ok(format!("{}: {}", render_ident(&f.name), f.render_type()?)), ", ")?),
+    };
     Ok(format!(
         indoc!(r#"
             {comment}
-            case {name}({ty})
+            {indirect}case {name}{payload}
         "#),
         comment=x.comment.commentize(),
-        name=x.name,
-        ty=x.content.render(x.span)?,
+        // Boxed content is usually a recursive reference.
+        indirect=if is_boxed { "indirect " } else { "" },
+        name=render_ident(&x.name),
+        payload=payload,
     )).trim()
 }
+/// Pattern of a `switch` case which binds payload of variant `x`.
+/// New-type payload is bound to `x`, and tuple or struct payload to `x0`, `x1`, ...
+fn render_payload_pattern(x:&KSumTypeVariant) -> String {
+    let n = match &x.form {
+        KSumTypeVariantForm::Newtype => return format!("case let .{}(x):", render_ident(&x.name)),
+        KSumTypeVariantForm::Unit => return format!("case .{}:", render_ident(&x.name)),
+        KSumTypeVariantForm::Tuple(xs) => xs.len(),
+        KSumTypeVariantForm::Struct(fs) => fs.len(),
+    };
+    let bindings = (0..n).map(|i| format!("x{}", i)).collect::<Vec<_>>();
+    format!("case let .{}({}):", render_ident(&x.name), bindings.join(", "))
+}
+/// Statements which decode variant `x` from `decoder` into `self`.
+/// Payload type is written only in type annotation, as a case of same name would shadow it in expressions.
+fn render_payload_decode(x:&KSumTypeVariant, decoder:&str) -> Result<String> {
+    match &x.form {
+        KSumTypeVariantForm::Newtype => Ok(format!("let x: {ty} = try .init(from: {decoder}); self = .{name}(x)", name=render_ident(&x.name), ty=x.content.render(x.span)?, decoder=decoder)),
+        KSumTypeVariantForm::Unit => Ok(format!("self = .{}", render_ident(&x.name))),
+        _ => Ok(format!("self = try Self.decode{name}(from: {decoder})", name=to_ident(&x.name).to_upper_camel(), decoder=decoder)),
+    }
+}
+/// Statements which encode payload bound by `render_payload_pattern` to `encoder`.
+fn render_payload_encode(x:&KSumTypeVariant, encoder:&str) -> Result<String> {
+    match &x.form {
+        KSumTypeVariantForm::Newtype => Ok(format!("try x.encode(to: {})", encoder)),
+        KSumTypeVariantForm::Unit => Ok(String::new()),
+        KSumTypeVariantForm::Tuple(xs) => {
+            let mut lines = vec![format!("var items = {}.unkeyedContainer()", encoder)];
+            lines.extend((0..xs.len()).map(|i| format!("try items.encode(x{})", i)));
+            Ok(lines.join("\n"))
+        },
+        KSumTypeVariantForm::Struct(fs) => {
+            let mut lines = vec![format!("var fields = {}.container(keyedBy: {}CodingKeys.self)", encoder, to_ident(&x.name).to_upper_camel())];
+            lines.extend(fs.iter().enumerate().map(|(i,f)| {
+                let method = if f.is_swift_optional() { "encodeIfPresent" } else { "encode" };
+                format!("try fields.{}(x{}, forKey: .{})", method, i, render_ident(&f.name))
+            }));
+            Ok(lines.join("\n"))
+        },
+    }
+}
+/// Static decoding function for tuple and struct variants.
+/// These need their own containers, so cannot be decoded in an expression.
+fn render_payload_decoder(x:&KSumTypeVariant) -> Option<Result<String>> {
+    let name = to_ident(&x.name).to_upper_camel();
+    match &x.form {
+        KSumTypeVariantForm::Newtype | KSumTypeVariantForm::Unit => None,
+        KSumTypeVariantForm::Tuple(xs) => Some((|| Ok(format!(
+            indoc!(r#"
+                private static func decode{name}(from decoder: Decoder) throws -> Self {{
+                    var items = try decoder.unkeyedContainer()
+                    return .{case}({items})
+                }}
+            "#),
+            name=name,
+            case=render_ident(&x.name),
+            items=xs.iter().map_join(|t| Ok(format!("try items.decode({}.self)", t.render(x.span)?)), ", ")?,
+        )).trim())()),
+        KSumTypeVariantForm::Struct(fs) => Some((|| Ok(format!(
+            indoc!(r#"
+                private enum {name}CodingKeys: String, CodingKey {{
+                {keys}
+                }}
+                private static func decode{name}(from decoder: Decoder) throws -> Self {{
+                    let fields = try decoder.container(keyedBy: {name}CodingKeys.self)
+                    return .{case}({fields})
+                }}
+            "#),
+            name=name,
+            case=render_ident(&x.name),
+            keys=render_coding_keys(fs)?.indent(),
+            fields=fs.iter().map_join(|f| {
+                let method = if f.is_swift_optional() { "decodeIfPresent" } else { "decode" };
+                Ok(format!("{name}: try fields.{method}({ty}.self, forKey: .{name})", name=render_ident(&f.name), method=method, ty=f.content.unwrap_option().render(f.span)?))
+            }, ", ")?,
+        )).trim())()),
+    }
+}
+
+impl KProdTypeField {
+    /// Rust side can omit this field on serialization, or accepts it missing.
+    fn is_swift_optional(&self) -> bool {
+        self.is_omittable()
+    }
+    /// Type of property or associated value.
+    fn render_type(&self) -> Result<String> {
+        let optionality = if self.is_omittable() && !self.content.is_option() { "?" } else { "" };
+        Ok(format!("{}{}", self.content.render(self.span)?, optionality))
+    }
+}
+impl KType {
+    fn unwrap_option(&self) -> &KType {
+        match self {
+            KType::Option(x) => x,
+            _ => self,
+        }
+    }
+}
 
 impl Swift5Rendering for KProdType {
     fn render(&self) -> Result<String> {
         Ok(format!(
             indoc!(r#"
                 {comment}
-                struct {name}: Equatable, Codable {{
-                {properties}
+                struct {name}{params}: Hashable, Codable {{
+                {properties}{coding_keys}
                 }}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
+            params=render_type_params(&self.type_params, CODABLE_BOUND),
             properties=self.fields.iter().map_join(KProdTypeField::render, "\n")?.indent(),
+            // Synthesized keys are same with property names.
+            coding_keys=match self.fields.iter().all(|x| to_ident(&x.name) == x.name) {
+                true => String::new(),
+                false => format!("\n{}", format!("private enum CodingKeys: String, CodingKey {{\n{}\n}}", render_coding_keys(&self.fields)?.indent()).indent()),
+            },
         ))
     }
 }
@@ -123,11 +619,51 @@ impl Swift5Rendering for KProdTypeField {
         Ok(format!(
             indoc!(r#"
                 {comment}
-                var {name}: {ty}
+                {wrapper}var {name}: {ty}
+            "#),
+            comment=self.comment.commentize(),
+            wrapper=if self.content.is_boxed() { "@Indirect " } else { "" },
+            name=render_ident(&self.name),
+            ty=self.render_type()?,
+        )).trim()
+    }
+}
+
+impl Swift5Rendering for KService {
+    fn render(&self) -> Result<String> {
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                protocol {name} {{
+                {operations}
+                }}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
-            ty=self.content.render(self.span)?,
+            operations=self.operations.iter().map_join(KOperation::render, "\n")?.indent(),
+        ))
+    }
+}
+impl Swift5Rendering for KOperation {
+    /// Remote calls can fail and take time.
+    fn render(&self) -> Result<String> {
+        let input = match &self.input {
+            KType::Unit => String::new(),
+            x => format!("_ input: {}", x.render(self.span)?),
+        };
+        let output = match &self.output {
+            KType::Unit => String::new(),
+            x => format!(" -> {}", x.render(self.span)?),
+        };
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                func {name}({input}) async throws{output}
+            "#),
+            comment=self.comment.commentize(),
+            name=self.name,
+            input=input,
+            output=output,
         )).trim()
     }
 }
@@ -141,7 +677,10 @@ impl Swift5Rendering for KFuncType {
             "#),
             comment=self.comment.commentize(),
             name=self.name,
-            input=self.input.render(self.span)?,
+            input=match &self.input {
+                KType::Unit => String::new(),
+                x => x.render(self.span)?,
+            },
             output=self.output.render(self.span)?,
         ))
     }
@@ -152,31 +691,108 @@ impl Swift5Rendering for KFuncType {
 
 
 
-impl Swift5RenderingWithSpan for KType {
-    fn render(&self, span:KSpan) -> Result<String> {
-        use KType::*;
-        match self {
-            Vector(x) => return Ok(format!("[{code}]", code=x.render(span)?)),
-            Option(x) => return Ok(format!("{code}?", code=x.render(span)?)),
-            Scalar(x) => x.render(span),
-            Never => return err(span, "never-type is not unsupported"),
-            Unknown => return err(span, "unsupported type pattern"),
+/// Swift identifier of a serialized name.
+/// Serialized names can be anything (e.g. `order-id`), and are kept in coding keys.
+fn render_ident(x:&str) -> String {
+    let x = to_ident(x);
+    if SWIFT_KEYWORDS.contains(&x.as_str()) { format!("`{}`", x) } else { x }
+}
+/// Identifier without escaping, to make a part of other identifiers.
+/// Names which are not identifiers become `lowerCamelCase`.
+fn to_ident(x:&str) -> String {
+    let is_ident = !x.is_empty() && x.chars().enumerate().all(|(i,c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if is_ident { return x.to_string() }
+    let mut z = String::new();
+    for (i,part) in x.split(|c:char| !c.is_ascii_alphanumeric()).filter(|x| !x.is_empty()).enumerate() {
+        // Words in `SCREAMING-KEBAB-CASE` are not kept in upper case.
+        let part = if part.chars().any(|c| c.is_ascii_lowercase()) { part.to_string() } else { part.to_ascii_lowercase() };
+        z.push_str(&if i == 0 { part.to_lower_camel() } else { part.to_upper_camel() });
+    }
+    if z.is_empty() || z.starts_with(|c:char| c.is_ascii_digit()) { z.insert(0, '_') }
+    z
+}
+/// Coding keys of fields with serialized names if they differ from property names.
+fn render_coding_keys(fields:&[KProdTypeField]) -> Result<String> {
+    let mut idents = Vec::new();
+    for x in fields.iter() {
+        let ident = to_ident(&x.name);
+        if idents.contains(&ident) { return err(x.span, &format!("serialized name `{}` conflicts with another field as Swift identifier `{}`", x.name, ident)) }
+        idents.push(ident);
+    }
+    Ok(fields.iter().map(|x| render_coding_key(&x.name)).collect::<Vec<_>>().join("\n"))
+}
+fn render_coding_key(x:&str) -> String {
+    if to_ident(x) == x { format!("case {}", render_ident(x)) } else { format!("case {} = {}", render_ident(x), render_string(x)) }
+}
+/// Swift string literal.
+fn render_string(x:&str) -> String {
+    let mut z = String::from("\"");
+    for c in x.chars() {
+        match c {
+            '\\' => z.push_str("\\\\"),
+            '"' => z.push_str("\\\""),
+            '\n' => z.push_str("\\n"),
+            '\r' => z.push_str("\\r"),
+            '\t' => z.push_str("\\t"),
+            c if c.is_control() => z.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => z.push(c),
         }
     }
+    z.push('"');
+    z
 }
-impl Swift5RenderingWithSpan for KScalarType {
+const SWIFT_KEYWORDS: &[&str] = &[
+    "associatedtype", "class", "deinit", "enum", "extension", "fileprivate", "func", "import", "init", "inout", "internal", "let", "open", "operator",
+    "private", "precedencegroup", "protocol", "public", "rethrows", "static", "struct", "subscript", "typealias", "var",
+    "break", "case", "catch", "continue", "default", "defer", "do", "else", "fallthrough", "for", "guard", "if", "in", "repeat", "return", "throw", "switch", "where", "while",
+    "Any", "as", "await", "false", "is", "nil", "self", "Self", "super", "throws", "true", "try",
+];
+
+/// `Hashable` is required to be an element of `Set`.
+const CODABLE_BOUND: &str = ": Hashable & Codable";
+/// Renders generic parameter clause with `bound` on each parameter.
+fn render_type_params(params:&[String], bound:&str) -> String {
+    if params.is_empty() { return String::new() }
+    let ps = params.iter().map(|x| format!("{}{}", x, bound)).collect::<Vec<_>>();
+    format!("<{}>", ps.join(", "))
+}
+
+impl Swift5RenderingWithSpan for KType {
     fn render(&self, span:KSpan) -> Result<String> {
-        use KScalarType::*;
+        use KType::*;
         use KPrimType::*;
         let x = match self {
-            Unit => return err(span, "unit-type (`()`) is not supported"),
+            Vector(x) => return Ok(format!("[{code}]", code=x.render(span)?)),
+            Option(x) => return Ok(format!("{code}?", code=x.render(span)?)),
+            // `JSONEncoder` encodes dictionaries with non-`String` keys as arrays.
+            Map(_,v) => return Ok(format!("[String: {code}]", code=v.render(span)?)),
+            Set(x) => return Ok(format!("Set<{code}>", code=x.render(span)?)),
+            Boxed(x) => return x.render(span),
             Def(x) => &x,
+            App(x,args) => return Ok(format!("{}<{}>", x, args.iter().map_join(|x| x.render(span), ", ")?)),
+            Int64(x,KInt64Encoding::String) => return Ok(format!("DecimalString<{code}>", code=Prim(x.clone()).render(span)?)),
+            Int64(x,_) => return Prim(x.clone()).render(span),
             Prim(Bool) => "Bool",
+            Prim(I8) => "Int8",
+            Prim(I16) => "Int16",
             Prim(I32) => "Int32",
             Prim(I64) => "Int64",
-            Prim(F32) => "Int32",
-            Prim(F64) => "Int64",
+            Prim(ISize) => "Int",
+            Prim(U8) => "UInt8",
+            Prim(U16) => "UInt16",
+            Prim(U32) => "UInt32",
+            Prim(U64) => "UInt64",
+            Prim(USize) => "UInt",
+            Prim(I128) => return err(span, "`i128` is not supported in Swift5"),
+            Prim(U128) => return err(span, "`u128` is not supported in Swift5"),
+            Prim(F32) => "Float",
+            Prim(F64) => "Double",
+            // `Character` is not `Codable`.
+            Prim(Char) => "String",
             Prim(String) => "String",
+            Unit => return err(span, "unit-type (`()`) is not supported"),
+            Never => return err(span, "never-type is not unsupported"),
+            Unknown => return err(span, "unsupported type pattern"),
         };
         Ok(x.to_string())
     }
@@ -226,6 +842,22 @@ impl String {
         if f { x.pop(); }
         x
     }
+    /// First letter in lower case to make a method name.
+    fn to_lower_camel(&self) -> String {
+        let mut cs = self.chars();
+        match cs.next() {
+            None => String::new(),
+            Some(c) => c.to_lowercase().chain(cs).collect(),
+        }
+    }
+    /// First letter in upper case to make a part of identifier.
+    fn to_upper_camel(&self) -> String {
+        let mut cs = self.chars();
+        match cs.next() {
+            None => String::new(),
+            Some(c) => c.to_uppercase().chain(cs).collect(),
+        }
+    }
     fn indent(&self) -> String {
         let mut x = String::new();
         let mut f = false;
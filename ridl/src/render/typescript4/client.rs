@@ -0,0 +1,235 @@
+//! HTTP client of REST endpoints.
+//! Uses types rendered by `render_typescript4`.
+
+use indoc::indoc;
+
+use crate::prelude::*;
+use crate::model::rest::{self, KEndpoint, KParamLocation};
+use super::*;
+
+type Defs<'a> = Map<&'a str, &'a KItem>;
+
+pub fn render_typescript4_client(x:&KMod) -> Result<String> {
+    let mut defs = Map::new();
+    x.collect_defs(&mut defs);
+    let endpoints = x.named_endpoints(|x| x.to_string().to_lower_camel(), &[])?;
+    Ok(format!(
+        indoc!(r#"
+            {runtime}
+
+            {functions}
+        "#),
+        runtime=RUNTIME.trim(),
+        functions=endpoints.iter().map_join(|(name,x)| x.render_typescript4_client(name, &defs), "\n\n")?,
+    ))
+}
+
+#[ext(name=KEndpointTypeScript4ClientRendering)]
+impl<'a> KEndpoint<'a> {
+    /// `#[rest(in)]` input is sent as parameters and body.
+    /// `#[rest(out)]` output is decoded by status code.
+    /// Any other input and output are JSON bodies.
+    fn render_typescript4_client(&self, name:&str, defs:&Defs) -> Result<String> {
+        let message_in = self.message_in(defs);
+        let escaped_path = self.path.replace('\\', "\\\\").replace('`', "\\`").replace('$', "\\$");
+        let path = rest::replace_placeholders(&escaped_path, |name| match message_in.and_then(|x| x.path_field(name)) {
+            None => err(self.span, &format!("path placeholder `{{{}}}` needs a `#[path]` field of `#[rest(in)]` input", name)),
+            Some(x) => Ok(format!("${{pathValue({})}}", render_property_access("input", &x.name))),
+        })?;
+        let mut queries = Vec::new();
+        let mut headers = Vec::new();
+        let mut cookies = Vec::new();
+        let mut body = "undefined".to_string();
+        match message_in {
+            Some(x) => {
+                for (f,location) in x.rest_params() {
+                    match location {
+                        KParamLocation::Path => (),
+                        KParamLocation::Query => queries.push(render_optional_guard(f, |x| match f.content.unwrap_option() {
+                            KType::Vector(_) | KType::Set(_) => format!("for (const x of {}) query.append({}, parameterValue(x))", x, render_string(&f.name)),
+                            _ => format!("query.append({}, parameterValue({}))", render_string(&f.name), x),
+                        })),
+                        KParamLocation::Header(name) => headers.push(render_optional_guard(f, |x| format!("headers[{}] = parameterValue({})", render_string(name), x))),
+                        KParamLocation::Cookie(name) => cookies.push(render_optional_guard(f, |x| format!("cookies.push({} + parameterValue({}))", render_string(&format!("{}=", name)), x))),
+                        KParamLocation::Body(_) => (),
+                    }
+                }
+                // Optional bodies are alternatives. The first present one is sent.
+                let bodies = x.rest_bodies();
+                if !bodies.is_empty() {
+                    headers.push(bodies.iter().map(|(f,mime)| render_optional_guard(f, |_| format!(r#"headers["Content-Type"] = {}"#, render_string(mime)))).collect::<Vec<_>>().join("\nelse "));
+                }
+                for (f,mime) in bodies.iter().rev() {
+                    let x = render_property_access("input", &f.name);
+                    body = match f.is_omittable() {
+                        true => format!("{x} != null ? encodeBody({x}, {mime}) : {rest}", x=x, mime=render_string(mime), rest=body),
+                        false => format!("encodeBody({}, {})", x, render_string(mime)),
+                    };
+                }
+            },
+            None if *self.input == KType::Unit => (),
+            None => {
+                headers.push(r#"headers["Content-Type"] = "application/json""#.to_string());
+                body = "JSON.stringify(input)".to_string();
+            },
+        }
+        if !cookies.is_empty() {
+            headers.push(format!(
+                indoc!(r#"
+                    // Works only outside browsers (e.g. Node). Browsers drop `Cookie` as a forbidden header.
+                    const cookies: string[] = []
+                    {cookies}
+                    if (cookies.length > 0) headers["Cookie"] = cookies.join("; ")
+                "#),
+                cookies=cookies.join("\n"),
+            ).trim().to_string());
+        }
+        let mut lines = Vec::new();
+        if !queries.is_empty() {
+            lines.push("const query = new URLSearchParams()".to_string());
+            lines.extend(queries.iter().cloned());
+        }
+        lines.push("const headers: Record<string, string> = {}".to_string());
+        lines.extend(headers);
+        lines.push(format!(
+            r#"const response = await send(config, "{method}", `{path}`, {query}, headers, {body})"#,
+            method=self.method,
+            path=path,
+            query=if queries.is_empty() { "undefined" } else { "query" },
+            body=body,
+        ));
+        lines.push(match self.message_out(defs) {
+            Some(x) => render_message_out_decoding(x)?,
+            None => render_default_decoding(self.output, self.span)?,
+        });
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                export async function {name}(config: ClientConfig{input}): Promise<{output}> {{
+                {lines}
+                }}
+            "#),
+            comment=self.comment.to_string().commentize(),
+            name=name,
+            input=match self.input {
+                KType::Unit => String::new(),
+                x => format!(", input: {}", x.render(self.span)?),
+            },
+            output=match self.output {
+                KType::Unit => "void".to_string(),
+                x => x.render(self.span)?,
+            },
+            lines=lines.join("\n").indent(),
+        )).trim()
+    }
+}
+
+/// Renders a statement on the field, guarded by existence if it is optional.
+fn render_optional_guard(f:&KProdTypeField, render:impl Fn(&str) -> String) -> String {
+    let x = render_property_access("input", &f.name);
+    if f.is_omittable() { format!("if ({} != null) {}", x, render(&x)) } else { render(&x) }
+}
+
+/// Any 2xx status is a success.
+fn render_default_decoding(output:&KType, span:KSpan) -> Result<String> {
+    let guard = "if (!response.ok) throw new HTTPStatusError(response.status, await response.text())";
+    match output {
+        KType::Unit => Ok(guard.to_string()),
+        x => Ok(format!("{}\nreturn await response.json() as {}", guard, x.render(span)?)),
+    }
+}
+
+/// Picks variant by status code, and then by MIME-Type if a status has multiple variants.
+/// `#[status(default)]` variants are tried last.
+fn render_message_out_decoding(x:&KSumType) -> Result<String> {
+    let (cases, is_exhaustive) = x.rest_response_cases()?;
+    let mut lines = Vec::new();
+    for case in cases.iter() {
+        let v = case.variant;
+        let payload = match case.mime {
+            None => None,
+            Some(mime) => Some(format!("await decodeBody(response, {}) as {}", render_string(mime), v.content.render_as_operand(v.span)?)),
+        };
+        let mut conditions = Vec::new();
+        if let Some(status) = case.status { conditions.push(format!("response.status === {}", status)) }
+        if let Some(mime) = case.content_type { conditions.push(format!("contentType.startsWith({})", render_string(mime))) }
+        let value = render_variant_value(x, v, payload.as_deref())?;
+        match conditions.is_empty() {
+            true => lines.push(format!("return {}", value)),
+            false => lines.push(format!("if ({}) return {}", conditions.join(" && "), value)),
+        }
+    }
+    if !is_exhaustive { lines.push("throw new HTTPStatusError(response.status, await response.text())".to_string()) }
+    let content_type = if cases.iter().any(|x| x.content_type.is_some()) {
+        "const contentType = response.headers.get(\"Content-Type\") ?? \"\"\n"
+    } else {
+        ""
+    };
+    Ok(format!("{}{}", content_type, lines.join("\n")))
+}
+
+/// Value of sum-type `x` in its serialization form.
+fn render_variant_value(x:&KSumType, v:&KSumTypeVariant, payload:Option<&str>) -> Result<String> {
+    use KSumTypeSerializationForm::*;
+    let value = match (&x.serialization, payload) {
+        (NameBased, None) => render_string(&v.name),
+        (NameBased, Some(p)) => format!("{{ {}: {} }}", render_property_name(&v.name), p),
+        (TypeBased { discriminant }, None) => format!("{{ {}: {} }}", render_property_name(discriminant), render_string(&v.name)),
+        (TypeBased { discriminant }, Some(p)) => format!("{{ {}: {}, ...({}) }}", render_property_name(discriminant), render_string(&v.name), p),
+        (Adjacent { tag, .. }, None) => format!("{{ {}: {} }}", render_property_name(tag), render_string(&v.name)),
+        (Adjacent { tag, content }, Some(p)) => format!("{{ {}: {}, {}: {} }}", render_property_name(tag), render_string(&v.name), render_property_name(content), p),
+        (Untagged, None) => "null".to_string(),
+        (Untagged, Some(p)) => p.to_string(),
+    };
+    Ok(value)
+}
+
+/// Transport and codecs used by endpoint functions.
+const RUNTIME: &str = indoc!(r#"
+    /// Sends HTTP requests. Same with `fetch`.
+    /// Inject a fake to test without network.
+    export type Fetch = (url: string, init: RequestInit) => Promise<Response>
+
+    export interface ClientConfig {
+        baseURL: string
+        /// Global `fetch` if omitted.
+        fetch?: Fetch
+        /// Browsers drop `Cookie` header set by script, and send their own cookies with `"include"`.
+        credentials?: RequestCredentials
+    }
+
+    /// Response with a status code not declared by endpoint.
+    export class HTTPStatusError extends Error {
+        constructor(readonly status: number, readonly body: string) {
+            super(`unexpected HTTP status ${status}`)
+        }
+    }
+
+    async function send(config: ClientConfig, method: string, path: string, query: URLSearchParams | undefined, headers: Record<string, string>, body: BodyInit | undefined): Promise<Response> {
+        const search = query && query.toString() ? `?${query}` : ""
+        const fetch = config.fetch ?? globalThis.fetch
+        return await fetch(`${config.baseURL.replace(/\/$/, "")}${path}${search}`, { method, headers, body, credentials: config.credentials })
+    }
+    /// Strings are sent as is, and other values in JSON.
+    function parameterValue(x: unknown): string {
+        if (typeof x === "string") return x
+        if (typeof x === "bigint") return x.toString()
+        return JSON.stringify(x)
+    }
+    function pathValue(x: unknown): string {
+        return encodeURIComponent(parameterValue(x))
+    }
+    /// Bytes and non-JSON strings are sent as is, and other values in JSON.
+    function encodeBody(x: unknown, contentType: string): BodyInit {
+        if (!contentType.includes("json")) {
+            if (typeof x === "string") return x
+            if (Array.isArray(x)) return new Uint8Array(x)
+        }
+        return JSON.stringify(x)
+    }
+    async function decodeBody(response: Response, contentType: string): Promise<unknown> {
+        if (contentType.includes("json")) return await response.json()
+        if (contentType.startsWith("text/")) return await response.text()
+        return Array.from(new Uint8Array(await response.arrayBuffer()))
+    }
+"#);
@@ -5,6 +5,9 @@ use crate::prelude::*;
 use crate::model::*;
 use crate::model::log::*;
 
+mod client;
+pub use client::render_typescript4_client;
+
 pub fn render_typescript4(x:&KMod) -> Result<String> {
     x.render()
 }
@@ -35,10 +38,12 @@ impl TypeScript4Rendering for KItem {
         match self {
             Mod(x) => x.render(),
             New(x) => x.render(),
+            Nominal(x) => x.render(),
             Enum(x) => x.render(),
             Sum(x) => x.render(),
             Prod(x) => x.render(),
             Func(x) => x.render(),
+            Service(x) => x.render(),
         }.trim()
     }
 }
@@ -48,15 +53,32 @@ impl TypeScript4Rendering for KNewType {
         Ok(format!(
             indoc!(r#"
                 {comment}   
-                type {name} = {origin}
+                type {name}{params} = {origin}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
+            params=render_type_params(&self.type_params),
             origin=self.origin.render(self.span)?,
         ))
     }
 }
 
+impl TypeScript4Rendering for KNominalType {
+    /// Branded type. Structurally same with origin, but cannot be assigned from origin or other brands.
+    fn render(&self) -> Result<String> {
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                type {name}{params} = {origin} & {{ readonly __brand: "{name}" }}
+            "#),
+            comment=self.comment.commentize(),
+            name=self.name,
+            params=render_type_params(&self.type_params),
+            origin=self.origin.render_as_operand(self.span)?,
+        ))
+    }
+}
+
 impl TypeScript4Rendering for KEnumType {
     fn render(&self) -> Result<String> {
         Ok(format!(
@@ -68,12 +90,17 @@ impl TypeScript4Rendering for KEnumType {
             "#),
             comment=self.comment.commentize(),
             name=self.name,
-            cases=self.cases.iter().map_join(render_case, ",\n")?.indent(),
+            cases=self.cases.iter().map_join(|x| render_case(x, self.is_integer()), ",\n")?.indent(),
         ))
     }
 }
-fn render_case(x:&KEnumTypeCase) -> Result<String> {
-    Ok(format!(r#"{name} = "{value}""#, name=x.name, value=x.name))
+/// Integer-represented enums become numeric enums.
+fn render_case(x:&KEnumTypeCase, is_integer:bool) -> Result<String> {
+    match (is_integer, x.value) {
+        (true, Some(value)) => Ok(format!("{name} = {value}", name=render_property_name(&x.name), value=value)),
+        (true, None) => err(x.span, "missing discriminant of integer-represented enum case"),
+        (false, _) => Ok(format!("{name} = {value}", name=render_property_name(&x.name), value=render_string(&x.name))),
+    }
 }
 
 impl TypeScript4Rendering for KSumType {
@@ -81,22 +108,108 @@ impl TypeScript4Rendering for KSumType {
         Ok(format!(
             indoc!(r#"
                 {comment}
-                type {name} = {variants}
+                type {name}{params} = {variants}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
-            variants=self.variants.iter().map_join(render_sum_type_variant, " | ")?,
+            params=render_type_params(&self.type_params),
+            variants=self.render_variants()?,
         ))
     }
 }
+impl KSumType {
+    fn render_variants(&self) -> Result<String> {
+        use KSumTypeSerializationForm::*;
+        match &self.serialization {
+            NameBased => self.variants.iter().map_join(render_sum_type_variant, " | "),
+            TypeBased { discriminant } => self.variants.iter().map_join(|x| render_type_based_sum_type_variant(x, discriminant), " | "),
+            Adjacent { tag, content } => self.variants.iter().map_join(|x| render_adjacent_sum_type_variant(x, tag, content), " | "),
+            Untagged => self.variants.iter().map_join(|x| Ok(render_payload(x)?.unwrap_or_else(|| "null".to_string())), " | "),
+        }
+    }
+}
+/// Type of serialized payload of a variant.
+/// `None` for unit variants.
+fn render_payload(x:&KSumTypeVariant) -> Result<Option<String>> {
+    match &x.form {
+        KSumTypeVariantForm::Newtype => Ok(Some(x.content.render(x.span)?)),
+        KSumTypeVariantForm::Unit => Ok(None),
+        KSumTypeVariantForm::Tuple(xs) => Ok(Some(format!("[{}]", xs.iter().map_join(|t| t.render(x.span), ", ")?))),
+        KSumTypeVariantForm::Struct(fs) => Ok(Some(format!("{{ {} }}", fs.iter().map_join(KProdTypeField::render_inline, ", ")?))),
+    }
+}
+/// Discriminated union member with literal tag field.
+fn render_type_based_sum_type_variant(x:&KSumTypeVariant, discriminant:&str) -> Result<String> {
+    let tag = render_property_name(discriminant);
+    match &x.form {
+        KSumTypeVariantForm::Unit => Ok(format!("{{ {tag}: {name} }}", tag=tag, name=render_string(&x.name))),
+        // Fields are stored next to the tag.
+        KSumTypeVariantForm::Struct(fs) => Ok(format!(
+            "{{ {tag}: {name}, {fields} }}",
+            tag=tag,
+            name=render_string(&x.name),
+            fields=fs.iter().map_join(KProdTypeField::render_inline, ", ")?,
+        )),
+        _ => Ok(format!(
+            "({{ {tag}: {name} }} & {ty})",
+            tag=tag,
+            name=render_string(&x.name),
+            ty=x.content.render(x.span)?,
+        )),
+    }
+}
+fn render_adjacent_sum_type_variant(x:&KSumTypeVariant, tag:&str, content:&str) -> Result<String> {
+    match render_payload(x)? {
+        None => Ok(format!("{{ {tag}: {name} }}", tag=render_property_name(tag), name=render_string(&x.name))),
+        Some(ty) => Ok(format!(
+            "{{ {tag}: {name}, {content}: {ty} }}",
+            tag=render_property_name(tag),
+            name=render_string(&x.name),
+            content=render_property_name(content),
+            ty=ty,
+        )),
+    }
+}
+/// Quotes property name if needed.
+fn render_property_name(x:&str) -> String {
+    let is_ident = x.chars().enumerate().all(|(i,c)| c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if is_ident && !x.is_empty() { x.to_string() } else { render_string(x) }
+}
+/// Property access with dot notation if possible.
+fn render_property_access(object:&str, x:&str) -> String {
+    match render_property_name(x) {
+        name if name == x => format!("{}.{}", object, name),
+        name => format!("{}[{}]", object, name),
+    }
+}
+/// JavaScript string literal.
+fn render_string(x:&str) -> String {
+    let mut z = String::from("\"");
+    for c in x.chars() {
+        match c {
+            '\\' => z.push_str("\\\\"),
+            '"' => z.push_str("\\\""),
+            '\n' => z.push_str("\\n"),
+            '\r' => z.push_str("\\r"),
+            '\t' => z.push_str("\\t"),
+            // Line separators end string literals in older runtimes.
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => z.push_str(&format!("\\u{:04x}", c as u32)),
+            c => z.push(c),
+        }
+    }
+    z.push('"');
+    z
+}
 fn render_sum_type_variant(x:&KSumTypeVariant) -> Result<String> {
+    // Unit variants are bare strings.
+    if x.form == KSumTypeVariantForm::Unit { return Ok(render_string(&x.name)) }
     Ok(format!(
         indoc!(r#"
             {{ {name}: {ty} }}
         "#),
         // comment=x.comment.commentize(),
-        name=x.name,
-        ty=x.content.render(x.span)?,
+        name=render_property_name(&x.name),
+        ty=render_payload(x)?.unwrap_or_default(),
     )).trim()
 }
 
@@ -105,27 +218,77 @@ impl TypeScript4Rendering for KProdType {
         Ok(format!(
             indoc!(r#"
                 {comment}
-                type {name} = {{
+                type {name}{params} = {{
                 {properties}
                 }}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
+            params=render_type_params(&self.type_params),
             properties=self.fields.iter().map_join(KProdTypeField::render, "\n")?.indent(),
         ))
     }
 }
 impl TypeScript4Rendering for KProdTypeField {
     fn render(&self) -> Result<String> {
-        let name_with_optionality = if self.content.is_option() { format!("{}?", self.name) } else { self.name.clone() };
         Ok(format!(
             indoc!(r#"
                 {comment}
-                {name}: {ty}
+                {property}
+            "#),
+            comment=self.comment.commentize(),
+            property=self.render_inline()?,
+        )).trim()
+    }
+}
+impl KProdTypeField {
+    /// Property signature without comment.
+    fn render_inline(&self) -> Result<String> {
+        let name = render_property_name(&self.name);
+        let name_with_optionality = if self.is_omittable() { format!("{}?", name) } else { name };
+        // Top-level optionality is encoded in field name.
+        let ty = match &self.content {
+            KType::Option(x) => x.render(self.span)?,
+            x => x.render(self.span)?,
+        };
+        Ok(format!("{}: {}", name_with_optionality, ty))
+    }
+}
+
+impl TypeScript4Rendering for KService {
+    fn render(&self) -> Result<String> {
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                interface {name} {{
+                {operations}
+                }}
             "#),
             comment=self.comment.commentize(),
-            name=name_with_optionality,
-            ty=self.content.render(self.span)?,
+            name=self.name,
+            operations=self.operations.iter().map_join(KOperation::render, "\n")?.indent(),
+        ))
+    }
+}
+impl TypeScript4Rendering for KOperation {
+    fn render(&self) -> Result<String> {
+        let input = match &self.input {
+            KType::Unit => String::new(),
+            x => format!("input: {}", x.render(self.span)?),
+        };
+        let output = match &self.output {
+            KType::Unit => "void".to_string(),
+            x => x.render(self.span)?,
+        };
+        Ok(format!(
+            indoc!(r#"
+                {comment}
+                {name}({input}): Promise<{output}>
+            "#),
+            comment=self.comment.commentize(),
+            name=self.name,
+            input=input,
+            output=output,
         )).trim()
     }
 }
@@ -136,11 +299,13 @@ impl TypeScript4Rendering for KFuncType {
             indoc!(r#"
                 {comment}
                 declare function {name}({input}): {output}
-                }}
             "#),
             comment=self.comment.commentize(),
             name=self.name,
-            input=self.input.render(self.span)?,
+            input=match &self.input {
+                KType::Unit => String::new(),
+                x => format!("input: {}", x.render(self.span)?),
+            },
             output=self.output.render(self.span)?,
         ))
     }
@@ -150,35 +315,57 @@ impl TypeScript4Rendering for KFuncType {
 
 
 
+fn render_type_params(params:&[String]) -> String {
+    if params.is_empty() { String::new() } else { format!("<{}>", params.join(", ")) }
+}
+
 impl TypeScript4RenderingWithSpan for KType {
     fn render(&self, span:KSpan) -> Result<String> {
         use KType::*;
-        match self {
-            Vector(x) => return Ok(format!("{code}[]", code=x.render(span)?)),
-            Option(x) => x.render(span), // Optionality need to be encoded in field, not type.
-            Scalar(x) => x.render(span),
-            Never => return err(span, "never-type is not unsupported"),
-            Unknown => return err(span, "unsupported type pattern"),
-        }
-    }
-}
-impl TypeScript4RenderingWithSpan for KScalarType {
-    fn render(&self, span:KSpan) -> Result<String> {
-        use KScalarType::*;
         use KPrimType::*;
         let x = match self {
-            Unit => return err(span, "unit-type (`()`) is not supported"),
+            Vector(x) => return Ok(format!("{code}[]", code=x.render_as_operand(span)?)),
+            Option(x) => return Ok(format!("{code} | null", code=x.render(span)?)),
+            Map(_,v) => return Ok(format!("Record<string, {code}>", code=v.render(span)?)),
+            Set(x) => return Ok(format!("{code}[]", code=x.render_as_operand(span)?)),
+            Boxed(x) => return x.render(span),
             Def(x) => &x,
+            App(x,args) => return Ok(format!("{}<{}>", x, args.iter().map_join(|x| x.render(span), ", ")?)),
+            Int64(_,KInt64Encoding::Number) => "number",
+            Int64(_,KInt64Encoding::BigInt) => "bigint",
+            Int64(_,KInt64Encoding::String) => "string",
             Prim(Bool) => "boolean",
-            Prim(I32) => "number",
-            Prim(I64) => return err(span, "`i64` is not supported in TypeScript"),
-            Prim(F32) => return err(span, "`f32` is not supported in TypeScript"),
-            Prim(F64) => "number",
+            Prim(I8) | Prim(I16) | Prim(I32) => "number",
+            Prim(U8) | Prim(U16) | Prim(U32) => "number",
+            // `number` cannot represent all 64-bit integers precisely.
+            Prim(I64) | Prim(ISize) | Prim(U64) | Prim(USize) => return err(span, "64-bit integer is not supported in TypeScript without an encoding (use `--int64` or `#[int64(...)]`)"),
+            Prim(I128) => return err(span, "`i128` is not supported in TypeScript"),
+            Prim(U128) => return err(span, "`u128` is not supported in TypeScript"),
+            Prim(F32) | Prim(F64) => "number",
+            Prim(Char) => "string",
             Prim(String) => "string",
+            Unit => return err(span, "unit-type (`()`) is not supported"),
+            Never => return err(span, "never-type is not unsupported"),
+            Unknown => return err(span, "unsupported type pattern"),
         };
         Ok(x.to_string())
     }
 }
+#[ext(name=KTypeTypeScript4OperandRendering)]
+impl KType {
+    /// Renders as an operand of `[]` or `&`. Union types need parentheses. (e.g. `(T | null)[]`)
+    fn render_as_operand(&self, span:KSpan) -> Result<String> {
+        let code = self.render(span)?;
+        let is_union = match self { KType::Boxed(x) => x.is_option(), x => x.is_option() };
+        Ok(if is_union { format!("({})", code) } else { code })
+    }
+    fn unwrap_option(&self) -> &KType {
+        match self {
+            KType::Option(x) => x,
+            _ => self,
+        }
+    }
+}
 
 
 
@@ -224,6 +411,14 @@ impl String {
         if f { x.pop(); }
         x
     }
+    /// First letter in lower case to make a function name.
+    fn to_lower_camel(&self) -> String {
+        let mut cs = self.chars();
+        match cs.next() {
+            None => String::new(),
+            Some(c) => c.to_lowercase().chain(cs).collect(),
+        }
+    }
     fn indent(&self) -> String {
         let mut x = String::new();
         let mut f = false;
@@ -1,5 +1,7 @@
 use crate::prelude::*;
-use crate::model::{KAttrs,KAttrREST};
+use crate::model::{KSpan,KAttrs,KAttrREST,KAttrSerde,KInt64Encoding,KPrimType,KSumTypeSerializationForm};
+use crate::model::rename::Rule;
+use crate::model::rest;
 use crate::model::log::*;
 use super::{ir, err, err_with};
 
@@ -8,24 +10,46 @@ pub(super) impl Vec<syn::Attribute> {
     fn scan(&self) -> Result<KAttrs> {
         const BAD_FORM_ERR: &'static str = "badly formed attribute";
         let mut x = KAttrs::default();
+        let mut with = None;
         for a in self.iter() {
             let ir = ir::Attr::try_from(a)?;
             let n = ir.name.as_str();
+            if n == "serde" {
+                for p in ir.params.iter() {
+                    if let Some(m) = scan_serde_param(a, p)? {
+                        if let KAttrSerde::With(_) = m { with = Some(a) }
+                        x.serde.push(m);
+                    }
+                }
+                continue;
+            }
             let k = ir.params.iter().next().map(ir::AttrParam::key).unwrap_or("");
+            if n == "int64" {
+                if ir.params.len() != 1 { return err_with(a, BAD_FORM_ERR) }
+                match k.parse::<KInt64Encoding>() {
+                    Ok(e) => x.int64 = Some(e),
+                    Err(_) => return err_with(a, "64-bit integer encoding must be one of `number`, `bigint` or `string`"),
+                }
+                continue;
+            }
             let m = match (n,k) {
                 ("rest","in") => KAttrREST::MessageIn,
                 ("rest","out") => KAttrREST::MessageOut,
-                // ("rest", _) => {
-                //     if ir.params.len() == 2 {} else { return err(a, BAD_FORM_ERR) }
-                //     let k = if let ir::AttrParam::Key(k) = &ir.params[0] { k } else { return err(a, BAD_FORM_ERR) };
-                //     let v = if let ir::AttrParam::Value(ir::AttrValue::String(v)) = &ir.params[1] { v } else { return err(a, BAD_FORM_ERR) };
-                //     x.rest.push(KAttrREST::FnMethod(k.to_string()));
-                //     x.rest.push(KAttrREST::FnPath(v.to_owned()));
-                //     continue;
-                // },
-                ("path","") => KAttrREST::PathParam,
+                ("rest", _) => {
+                    if ir.params.len() == 2 {} else { return err_with(a, BAD_FORM_ERR) }
+                    let k = if let ir::AttrParam::Key(k) = &ir.params[0] { k } else { return err_with(a, BAD_FORM_ERR) };
+                    let v = if let ir::AttrParam::Value(ir::AttrValue::String(v)) = &ir.params[1] { v } else { return err_with(a, BAD_FORM_ERR) };
+                    if !rest::HTTP_METHODS.contains(&k.as_str()) { return err_with(a, &format!("unknown HTTP method `{}` (use one of {})", k, rest::HTTP_METHODS.join(", "))) }
+                    if let Err(x) = rest::path_placeholders(v) { return err_with(a, &x) }
+                    x.rest.push(KAttrREST::FnMethod(k.to_string()));
+                    x.rest.push(KAttrREST::FnPath(v.to_owned()));
+                    continue;
+                },
+                // Same with `#[path]`, which cannot be a helper of `RIDL` derive as a built-in attribute.
+                ("path","") | ("path_param","") => KAttrREST::PathParam,
                 ("query","") => KAttrREST::QueryParam,
                 ("body","") => KAttrREST::BodyParam,
+                ("status","default") => KAttrREST::DefaultStatus,
                 _ => {
                     let v = ir.params.iter().next().map(ir::AttrParam::value);
                     match (n,v) {
@@ -33,15 +57,120 @@ pub(super) impl Vec<syn::Attribute> {
                         ("status",_) => return err_with(a, BAD_FORM_ERR),
                         ("mime",Some(Some(ir::AttrValue::String(x)))) => KAttrREST::MIME(x.to_owned()),
                         ("mime",_) => return err_with(a, BAD_FORM_ERR),
+                        ("header",None) => KAttrREST::HeaderParam(None),
+                        ("header",Some(Some(ir::AttrValue::String(x)))) if ir.params.len() == 1 => KAttrREST::HeaderParam(Some(x.to_owned())),
+                        ("header",_) => return err_with(a, BAD_FORM_ERR),
+                        ("cookie",None) => KAttrREST::CookieParam(None),
+                        ("cookie",Some(Some(ir::AttrValue::String(x)))) if ir.params.len() == 1 => KAttrREST::CookieParam(Some(x.to_owned())),
+                        ("cookie",_) => return err_with(a, BAD_FORM_ERR),
                         _ => continue,
                     }
                 },
             };
             x.rest.push(m);
         }
+        if let Some(a) = with {
+            if x.int64 != Some(KInt64Encoding::String) { return err_with(a, "`with` serde attribute is supported only with `#[int64(string)]`") }
+        }
         Ok(x)
     }
+    /// Scans integer type of an enum serialized by `serde_repr`.
+    /// Plain `#[repr(...)]` does not change serialized form.
+    fn scan_enum_repr(&self, item:&syn::ItemEnum) -> Result<Option<KPrimType>> {
+        let mut repr = None;
+        let mut uses_serde_repr = false;
+        for a in self.iter() {
+            let ir = ir::Attr::try_from(a)?;
+            match ir.name.as_str() {
+                "derive" => uses_serde_repr |= ir.params.iter().any(|x| matches!(x.key(), "Serialize_repr" | "Deserialize_repr")),
+                "repr" => repr = repr.or(ir.params.iter().find_map(|x| scan_int_prim(x.key()))),
+                _ => (),
+            }
+        }
+        match (uses_serde_repr, repr) {
+            (false, _) => Ok(None),
+            (true, Some(x)) => Ok(Some(x)),
+            (true, None) => err_with(&item.ident, "`serde_repr` requires integer `#[repr(...)]` attribute"),
+        }
+    }
+}
+fn scan_int_prim(x:&str) -> Option<KPrimType> {
+    use KPrimType::*;
+    match x {
+        "i8" => Some(I8),
+        "i16" => Some(I16),
+        "i32" => Some(I32),
+        "i64" => Some(I64),
+        "i128" => Some(I128),
+        "isize" => Some(ISize),
+        "u8" => Some(U8),
+        "u16" => Some(U16),
+        "u32" => Some(U32),
+        "u64" => Some(U64),
+        "u128" => Some(U128),
+        "usize" => Some(USize),
+        _ => None,
+    }
+}
+#[ext(name=KAttrsSumTypeFormScan)]
+pub(super) impl KAttrs {
+    /// Takes out serialization form attributes of a sum-type.
+    fn take_sum_type_form(&mut self, span:KSpan) -> Result<KSumTypeSerializationForm> {
+        use KSumTypeSerializationForm::*;
+        let tag = self.serde.iter().find_map(|x| if let KAttrSerde::Tag(x) = x { Some(x.clone()) } else { None });
+        let content = self.serde.iter().find_map(|x| if let KAttrSerde::Content(x) = x { Some(x.clone()) } else { None });
+        let untagged = self.serde.contains(&KAttrSerde::Untagged);
+        self.serde.retain(|x| !x.is_sum_type_form());
+        match (tag, content, untagged) {
+            (None, None, false) => Ok(NameBased),
+            (Some(tag), None, false) => Ok(TypeBased { discriminant: tag }),
+            (Some(tag), Some(content), false) => Ok(Adjacent { tag: tag, content: content }),
+            (None, None, true) => Ok(Untagged),
+            (None, Some(_), _) => err(span, "`content` attribute requires `tag` attribute"),
+            _ => err(span, "`untagged` attribute cannot be used with `tag` or `content` attribute"),
+        }
+    }
+    /// Whether there's any serialization form attribute which is valid only on sum-types.
+    fn has_sum_type_form(&self) -> bool {
+        self.serde.iter().any(KAttrSerde::is_sum_type_form)
+    }
+}
+impl KAttrSerde {
+    fn is_sum_type_form(&self) -> bool {
+        matches!(self, KAttrSerde::Tag(_) | KAttrSerde::Content(_) | KAttrSerde::Untagged)
+    }
+}
+
+/// Scans one parameter of `#[serde(...)]` attribute.
+/// Returns `None` for parameters which do not affect serialization form.
+fn scan_serde_param(a:&syn::Attribute, p:&ir::AttrParam) -> Result<Option<KAttrSerde>> {
+    use ir::AttrParam::*;
+    use ir::AttrValue;
+    let m = match p {
+        KeyValue(k,AttrValue::String(v)) if k == "rename" => KAttrSerde::Rename(v.to_owned()),
+        KeyValue(k,AttrValue::String(v)) if k == "rename_all" => match v.parse::<Rule>() {
+            Ok(rule) => KAttrSerde::RenameAll(rule),
+            Err(_) => return err_with(a, &format!("unknown `rename_all` rule `{}`", v)),
+        },
+        Key(k) if k == "skip" => KAttrSerde::Skip,
+        Key(k) if k == "default" => KAttrSerde::Default,
+        KeyValue(k,AttrValue::String(_)) if k == "default" => KAttrSerde::Default,
+        KeyValue(k,AttrValue::String(v)) if k == "skip_serializing_if" => KAttrSerde::SkipSerializingIf(v.to_owned()),
+        KeyValue(k,AttrValue::String(v)) if k == "with" => KAttrSerde::With(v.to_owned()),
+        KeyValue(k,AttrValue::String(v)) if k == "tag" => KAttrSerde::Tag(v.to_owned()),
+        KeyValue(k,AttrValue::String(v)) if k == "content" => KAttrSerde::Content(v.to_owned()),
+        Key(k) if k == "untagged" => KAttrSerde::Untagged,
+        // No effect on serialized form.
+        Key(k) if k == "deny_unknown_fields" => return Ok(None),
+        KeyValue(k,_) if k == "bound" || k == "crate" || k == "expecting" => return Ok(None),
+        _ => {
+            let k = match p { Key(k) | KeyValue(k,_) => k.as_str(), Value(_) => "" };
+            return err_with(a, &format!("unsupported serde attribute `{}` (RIDL cannot reproduce its serialization form)", k))
+        },
+    };
+    Ok(Some(m))
 }
+
 impl ir::AttrParam {
     fn key(&self) -> &str {
         if let ir::AttrParam::Key(s) = self { &s } else { "" }
@@ -73,6 +202,76 @@ mod test {
             rest: vec![
                 KAttrREST::MessageIn,
             ],
+            serde: vec![],
+            int64: None,
         });
     }
+
+    #[test]
+    fn scan_serde() {
+        let a = quote! {
+            #[serde(rename="b", rename_all="camelCase")]
+            #[serde(default, skip_serializing_if="Option::is_none")]
+        };
+        let c = syn::parse::Parser::parse2(syn::Attribute::parse_outer, a).unwrap();
+        let d = c.scan().unwrap();
+        assert_eq!(d.serde, vec![
+            KAttrSerde::Rename("b".to_owned()),
+            KAttrSerde::RenameAll(Rule::CamelCase),
+            KAttrSerde::Default,
+            KAttrSerde::SkipSerializingIf("Option::is_none".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn scan_int64() {
+        let a = quote! {
+            #[int64(string)]
+            #[serde(with="string")]
+        };
+        let c = syn::parse::Parser::parse2(syn::Attribute::parse_outer, a).unwrap();
+        let d = c.scan().unwrap();
+        assert_eq!(d.int64, Some(KInt64Encoding::String));
+        assert_eq!(d.serde, vec![KAttrSerde::With("string".to_owned())]);
+        let a = quote! {
+            #[serde(with="string")]
+        };
+        let c = syn::parse::Parser::parse2(syn::Attribute::parse_outer, a).unwrap();
+        assert!(c.scan().is_err());
+    }
+
+    #[test]
+    fn scan_rest_params() {
+        let a = quote! {
+            #[header]
+            #[header("X-Request-Id")]
+            #[cookie("session")]
+        };
+        let c = syn::parse::Parser::parse2(syn::Attribute::parse_outer, a).unwrap();
+        let d = c.scan().unwrap();
+        assert_eq!(d.rest, vec![
+            KAttrREST::HeaderParam(None),
+            KAttrREST::HeaderParam(Some("X-Request-Id".to_owned())),
+            KAttrREST::CookieParam(Some("session".to_owned())),
+        ]);
+        let a = quote! {
+            #[status(default)]
+        };
+        let c = syn::parse::Parser::parse2(syn::Attribute::parse_outer, a).unwrap();
+        assert_eq!(c.scan().unwrap().rest, vec![KAttrREST::DefaultStatus]);
+        let a = quote! {
+            #[cookie(session)]
+        };
+        let c = syn::parse::Parser::parse2(syn::Attribute::parse_outer, a).unwrap();
+        assert!(c.scan().is_err());
+    }
+
+    #[test]
+    fn scan_unsupported_serde() {
+        let a = quote! {
+            #[serde(flatten)]
+        };
+        let c = syn::parse::Parser::parse2(syn::Attribute::parse_outer, a).unwrap();
+        assert!(c.scan().is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,225 @@
+//! Checks over whole scanned module.
+//! Some constraints cannot be checked while scanning a single item.
+
+use crate::prelude::*;
+use crate::model::*;
+use crate::model::log::*;
+use crate::model::recursion::KStorage;
+use crate::model::rest;
+use super::err;
+
+pub(super) fn check(m:&KMod) -> Result<()> {
+    let mut defs = Map::new();
+    m.collect_defs(&mut defs);
+    m.items.map_collect_result(|x| x.check(&defs))?;
+    for cycle in m.find_alias_cycles() {
+        let message = format!("recursive type alias `{}` cannot be expanded (use a struct to make a recursive type)", cycle.join("`, `"));
+        err(*defs[cycle[0]].span(), &message)?;
+    }
+    // Without any indirection, a recursive type has infinite size.
+    for cycle in m.find_cycles(|x| x == KStorage::Direct) {
+        let message = format!("recursive type `{}` has infinite size (put the recursive reference in `Option`, `Vec` or `Box`)", cycle.join("`, `"));
+        err(*defs[cycle[0]].span(), &message)?;
+    }
+    Ok(())
+}
+
+type Defs<'a> = Map<&'a str, &'a KItem>;
+
+impl KItem {
+    fn check(&self, defs:&Defs) -> Result<()> {
+        let attrs = match self {
+            KItem::New(x) => Some(&x.attrs),
+            KItem::Nominal(x) => Some(&x.attrs),
+            KItem::Enum(x) => Some(&x.attrs),
+            KItem::Sum(x) => Some(&x.attrs),
+            KItem::Prod(x) => Some(&x.attrs),
+            _ => None,
+        };
+        if attrs.map_or(false, |x| x.rest_method().is_some()) {
+            return err(*self.span(), "`#[rest(METHOD, \"/path\")]` is supported only on function-type aliases and service trait methods")
+        }
+        match self {
+            KItem::Mod(x) => { x.items.map_collect_result(|x| x.check(defs))?; },
+            KItem::New(x) => x.origin.check(x.span, defs)?,
+            KItem::Nominal(x) => x.origin.check(x.span, defs)?,
+            KItem::Sum(x) => x.check(defs)?,
+            KItem::Prod(x) => {
+                x.fields.map_collect_result(|x| x.content.check(x.span, defs))?;
+                for f in x.fields.iter().filter(|f| f.attrs.rest.contains(&KAttrREST::PathParam)) {
+                    if !f.content.is_path_param(defs) { return err(f.span, "`#[path]` field must be a non-optional scalar (a primitive, an enum-type or a new-type of them)") }
+                }
+                if x.attrs.rest.contains(&KAttrREST::MessageIn) { x.check_message_in()? }
+            },
+            KItem::Func(x) => {
+                x.input.check(x.span, defs)?;
+                x.output.check(x.span, defs)?;
+                check_path_params(x.span, &x.attrs, &x.input, defs)?;
+            },
+            KItem::Service(x) => { x.operations.map_collect_result(|x| {
+                x.input.check(x.span, defs)?;
+                x.output.check(x.span, defs)?;
+                check_path_params(x.span, &x.attrs, &x.input, defs)
+            })?; },
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Every placeholder in REST path must be matched by a `#[path]` field of input message, and vice versa.
+/// A field matches by its Rust name or serialized name. (See `KProdType::path_field`)
+fn check_path_params(span:KSpan, attrs:&KAttrs, input:&KType, defs:&Defs) -> Result<()> {
+    let path = match attrs.rest_path() { None => return Ok(()), Some(x) => x };
+    let names = match rest::path_placeholders(path) {
+        Err(x) => return err(span, &x),
+        Ok(x) => x,
+    };
+    let message = match input {
+        KType::Def(name) => match defs.get(name.as_str()) {
+            Some(KItem::Prod(x)) => Some(x),
+            _ => None,
+        },
+        _ => None,
+    };
+    for name in names.iter() {
+        let found = message.and_then(|x| x.path_field(name)).is_some();
+        if !found { return err(span, &format!("path placeholder `{{{}}}` is not matched by any `#[path]` field of input message", name)) }
+    }
+    let message = match message { None => return Ok(()), Some(x) => x };
+    for (i,f) in message.fields.iter().enumerate() {
+        if f.rest_location() != Some(rest::KParamLocation::Path) { continue }
+        let found = names.iter().any(|name| message.path_field_index(name) == Some(i));
+        if !found { return err(f.span, &format!("`#[path]` field `{}` is not matched by any placeholder in path `{}`", f.name, path)) }
+    }
+    Ok(())
+}
+
+impl KSumType {
+    fn check(&self, defs:&Defs) -> Result<()> {
+        use KSumTypeSerializationForm::*;
+        for x in self.variants.iter() {
+            let mut r = Ok(());
+            x.for_each_type(&mut |t| if r.is_ok() { r = t.check(x.span, defs) });
+            r?;
+        }
+        if self.attrs.rest.contains(&KAttrREST::MessageOut) { self.check_message_out()? }
+        match &self.serialization {
+            NameBased | Adjacent { .. } | Untagged => (),
+            TypeBased { .. } => {
+                self.variants.map_collect_result(|x| {
+                    match (&x.form, &x.content) {
+                        (KSumTypeVariantForm::Unit, _) | (KSumTypeVariantForm::Struct(_), _) => Ok(()),
+                        (KSumTypeVariantForm::Newtype, KType::Def(name)) if matches!(defs.get(name.as_str()), Some(KItem::Prod(_))) => Ok(()),
+                        _ => err(x.span, "internally tagged sum-type variant must be a unit, a struct or hold a prod-type (struct) reference"),
+                    }
+                })?;
+            },
+        }
+        Ok(())
+    }
+}
+
+impl KProdType {
+    /// Every field of `#[rest(in)]` message needs a location, and a MIME-Type if it is a body.
+    /// Each MIME-Type can appear only once in body fields, and multiple bodies are optional alternatives.
+    fn check_message_in(&self) -> Result<()> {
+        let mut mimes = Vec::new();
+        // Skipped fields are not on wire.
+        for x in self.fields.iter().filter(|x| !x.attrs.has_serde_skip()) {
+            if x.attrs.rest.contains(&KAttrREST::BodyParam) {
+                let mime = match x.attrs.rest_mime() {
+                    None => return err(x.span, "`#[body]` field needs `#[mime(\"...\")]` attribute"),
+                    Some(mime) => mime,
+                };
+                if mimes.contains(&mime) { return err(x.span, &format!("duplicate request body MIME-Type `{}`", mime)) }
+                mimes.push(mime);
+            }
+            if x.rest_location().is_none() {
+                return err(x.span, "field of `#[rest(in)]` message needs one of `#[path]`, `#[query]`, `#[header]`, `#[cookie]` or `#[body]`")
+            }
+        }
+        // Only one of bodies can be sent.
+        let bodies = self.rest_bodies();
+        if bodies.len() > 1 {
+            if let Some((x,_)) = bodies.iter().find(|(x,_)| !x.content.is_option()) {
+                return err(x.span, "`#[body]` field must be `Option` if there are other `#[body]` fields to send instead")
+            }
+        }
+        Ok(())
+    }
+}
+
+impl KSumType {
+    /// Every variant of `#[rest(out)]` message needs a status, and a MIME-Type if it has payload.
+    /// Each status and MIME-Type combination can appear only once.
+    fn check_message_out(&self) -> Result<()> {
+        let mut responses = Vec::<(String, Option<&str>)>::new();
+        for x in self.variants.iter() {
+            let status = match x.attrs.rest_status_key() {
+                None => return err(x.span, "`#[rest(out)]` variant needs `#[status(...)]` attribute"),
+                Some(status) => status,
+            };
+            let mime = match (&x.form, x.attrs.rest_mime()) {
+                (KSumTypeVariantForm::Tuple(_) | KSumTypeVariantForm::Struct(_), _) => return err(x.span, "`#[rest(out)]` variant must be a unit or a new-type"),
+                (KSumTypeVariantForm::Unit, _) => None,
+                (_, None) => return err(x.span, "`#[rest(out)]` variant with payload needs `#[mime(\"...\")]` attribute"),
+                (_, Some(mime)) => Some(mime),
+            };
+            if responses.contains(&(status.clone(), mime)) {
+                return err(x.span, &format!("duplicate response for status `{}` and MIME-Type `{}`", status, mime.unwrap_or("(none)")))
+            }
+            if x.attrs.rest_headers().contains(&None) {
+                return err(x.span, "response header needs a name like `#[header(\"Location\")]`")
+            }
+            responses.push((status, mime));
+        }
+        Ok(())
+    }
+}
+
+impl KType {
+    fn check(&self, span:KSpan, defs:&Defs) -> Result<()> {
+        match self {
+            KType::Map(k,_) if !k.is_valid_map_key(defs) => err(span, "map key must be a string, a char, an integer or an enum-type"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl KType {
+    /// Values which make exactly one path segment.
+    fn is_path_param(&self, defs:&Defs) -> bool {
+        match self {
+            KType::Prim(_) | KType::Int64(_,_) => true,
+            KType::Boxed(x) => x.is_path_param(defs),
+            KType::Def(name) => match defs.get(name.as_str()) {
+                Some(KItem::Enum(_)) => true,
+                Some(KItem::New(x)) => x.origin.is_path_param(defs),
+                Some(KItem::Nominal(x)) => x.origin.is_path_param(defs),
+                // Type parameters or undefined types. Cannot be checked.
+                None => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+    /// Keys which `serde` can serialize as strings.
+    fn is_valid_map_key(&self, defs:&Defs) -> bool {
+        use KPrimType::*;
+        match self {
+            KType::Prim(Char) | KType::Prim(String) => true,
+            KType::Boxed(x) => x.is_valid_map_key(defs),
+            KType::Prim(x) => x.is_integer(),
+            KType::Def(name) => match defs.get(name.as_str()) {
+                Some(KItem::Enum(_)) => true,
+                Some(KItem::New(x)) => x.origin.is_valid_map_key(defs),
+                Some(KItem::Nominal(x)) => x.origin.is_valid_map_key(defs),
+                // Type parameters or undefined types. Cannot be checked.
+                None => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
@@ -25,7 +25,7 @@ pub fn scan_attrs(x:&Vec<syn::Attribute>) -> Result<Vec<Attr>> {
 /// Reduce Rust attribute syntax to a simpler form.
 /// An attribute can be one of these forms.
 /// 
-/// ```compile_fail
+/// ```text
 ///     #[a]
 ///     #[a(b,c,d)]
 ///     #[a("B",222,false)]
@@ -1,7 +1,9 @@
-use super::{Attr, Type, Result, err, err_with, scan_attrs, scan_type};
+use syn::spanned::Spanned;
+use super::{Attr, Type, Result, SpanScan, err_with, scan_attrs, scan_type};
 
 /// - This does not scan receiver, argument names and argument attributes.
 pub struct Fn {
+    pub span: super::Span,
     pub attrs: Vec<Attr>,
     pub name: String,
     pub input: Vec<Type>,
@@ -19,6 +21,7 @@ pub fn scan_fn(x:&syn::ItemFn) -> Result<Fn> {
         }
     }
     Ok(Fn {
+        span: x.span().scan(),
         attrs: scan_attrs(&x.attrs)?,
         name: x.sig.ident.to_string(),
         input: input,
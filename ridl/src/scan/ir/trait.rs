@@ -1,9 +1,13 @@
-use super::{Type, Attr, Unknown, Result, Fn, unknown, err, err_with, scan_type, scan_attrs};
+use syn::spanned::Spanned;
+use super::{Unknown, Result, Fn, SpanScan, unknown, err_with, scan_type, scan_attrs};
 
 /// Simplified representation of a trait.
 /// - A trait can contain several items.
 /// - A trait can define functions.
+/// - A trait cannot have generic parameters.
 pub struct Trait {
+    pub span: super::Span,
+    pub name: String,
     pub items: Vec<TraitItem>,
 }
 pub enum TraitItem {
@@ -13,7 +17,10 @@ pub enum TraitItem {
 
 pub fn scan_trait(x:&syn::ItemTrait) -> Result<Trait> {
     use crate::model::log::*;
+    if !x.generics.params.is_empty() { return err_with(&x.generics, "generic parameter is not supported on trait") }
     Ok(Trait {
+        span: x.span().scan(),
+        name: x.ident.to_string(),
         items: x.items.map_collect_result(scan_trait_item)?,
     })
 }
@@ -40,6 +47,7 @@ fn scan_trait_fn(x:&syn::TraitItemMethod) -> Result<Fn> {
         }
     }
     Ok(Fn {
+        span: x.span().scan(),
         attrs: scan_attrs(&x.attrs)?,
         name: x.sig.ident.to_string(),
         input: input,
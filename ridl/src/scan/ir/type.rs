@@ -71,6 +71,12 @@ pub fn scan_type(x:&syn::Type) -> Result<Type> {
         Macro(x) => err_with(x, BAD_FORM),
         Never(x) => err_with(x, BAD_FORM),
         TraitObject(x) => err_with(x, BAD_FORM),
+        Tuple(x) if x.elems.is_empty() => Ok(Type {
+            span: x.span().scan(),
+            path: String::new(),
+            name: "()".to_string(),
+            params: Vec::new(),
+        }),
         Tuple(x) => err_with(x, BAD_FORM),
         Verbatim(x) => err_with(x, BAD_FORM),
         _ => err_with(x, "unknown type case")
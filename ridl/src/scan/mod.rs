@@ -3,19 +3,28 @@ mod ty;
 mod ir;
 mod attr;
 mod err;
+mod check;
 
 use std::string::ToString;
 use syn::spanned::Spanned;
+use syn::ext::IdentExt;
 use extend::ext;
 
 use crate::prelude::*;
 use crate::model::*;
 use crate::model::log::*;
 use span::SpanScan;
-use attr::VecAttrScan;
+use attr::{VecAttrScan, KAttrsSumTypeFormScan};
 
 pub fn scan(x: &syn::File) -> Result<KMod> {
-    x.scan()
+    let m = x.scan()?;
+    check::check(&m)?;
+    Ok(m)
+}
+
+/// Checks a model which is not scanned from Rust code. (e.g. RIDL1 YAML)
+pub fn check(x: &KMod) -> Result<()> {
+    check::check(x)
 }
 
 #[ext(name=FileScan)]
@@ -40,7 +49,7 @@ impl syn::Item {
             Type(x) => Ok(Some(x.scan()?)),
             Enum(x) => Ok(Some(x.scan()?)),
             Struct(x) => Ok(Some(x.scan()?)),
-            // Trait(x)
+            Trait(x) => Ok(Some(x.scan()?)),
             // Union(x) 
             _ => err_with(&self, "unsupported item")
         }
@@ -68,35 +77,22 @@ impl syn::ItemType {
     /// Scans a type-alias.
     /// Maps to a new-type in RIDL schema.
     fn scan(&self) -> Result<KItem> {
-        if !self.generics.params.is_empty() { return err_with(&self.generics, "generic parameter is not supported") }
         match &*self.ty {
-            // syn::Type::TraitObject(_) => {
-            //     let form = self.ty.scan_func_type_form()?;
-            //     Ok(KItem::Func(KFuncType {
-            //         span: self.span().scan(),
-            //         name: self.ident.to_string(),
-            //         attrs: self.attrs.scan()?,
-            //         comment: self.attrs.scan_doc_comment()?,
-            //         input: KTypeRef::Def(KItemPath { span: self.ty.span().scan(), name: form.input_type_name.clone() }),
-            //         output: KTypeRef::Def(KItemPath { span: self.ty.span().scan(), name: form.output_type_name.clone() }),
-            //     }))
-            // },
-            // syn::Type::BareFn(x) => {
-            //     if x.inputs.len() == 1 {} else { return err_with(&x.inputs, "only 1 input argument is supported") }
-            //     let input_ty = &x.inputs[0].ty;
-            //     let output_ty = if let syn::ReturnType::Type(_,ty) = &x.output { ty } else { return err_with(&x.output, "output type must be specified explicitly") };
-            //     Ok(KItem::Func(KFuncType {
-            //         span: self.span().scan(),
-            //         name: self.ident.to_string(),
-            //         attrs: self.attrs.scan()?,
-            //         comment: self.attrs.scan_doc_comment()?,
-            //         input: ty::scan(input_ty)?,
-            //         output: ty::scan(output_ty)?,
-            //     }))
-            // },
+            syn::Type::TraitObject(_) | syn::Type::BareFn(_) => {
+                let (input,output) = self.ty.scan_func_type_form()?;
+                Ok(KItem::Func(KFuncType {
+                    span: self.span().scan(),
+                    name: self.ident.to_string(),
+                    attrs: self.attrs.scan()?,
+                    comment: self.attrs.scan_doc_comment()?,
+                    input: input,
+                    output: output,
+                }))
+            },
             _ => Ok(KItem::New(KNewType {
                 span: self.span().scan(),
                 name: self.ident.to_string(),
+                type_params: self.generics.scan_type_params()?,
                 attrs: self.attrs.scan()?,
                 comment: self.attrs.scan_doc_comment()?,
                 origin: ty::scan(&self.ty)?,
@@ -105,17 +101,73 @@ impl syn::ItemType {
     }
 }
 
+#[ext(name=TypeFuncScan)]
+impl syn::Type {
+    /// Scans input and output types of `dyn Fn(A) -> B` or `fn(A) -> B`.
+    /// Input is `KType::Unit` if there's no argument.
+    fn scan_func_type_form(&self) -> Result<(KType,KType)> {
+        let (inputs,output) = match self {
+            syn::Type::TraitObject(x) => {
+                let mut bounds = x.bounds.iter().filter_map(|x| match x {
+                    syn::TypeParamBound::Trait(x) => Some(x),
+                    syn::TypeParamBound::Lifetime(_) => None,
+                });
+                let seg = match (bounds.next(), bounds.next()) {
+                    (Some(x), None) => x.path.segments.last(),
+                    _ => None,
+                };
+                match seg {
+                    Some(syn::PathSegment { ident, arguments: syn::PathArguments::Parenthesized(x) }) if ident == "Fn" || ident == "FnMut" || ident == "FnOnce" => {
+                        (x.inputs.iter().collect::<Vec<_>>(), &x.output)
+                    },
+                    _ => return err_with(self, "only `dyn Fn(A) -> B` form is supported in trait object"),
+                }
+            },
+            syn::Type::BareFn(x) => {
+                if x.variadic.is_some() { return err_with(x, "variadic function is not supported") }
+                (x.inputs.iter().map(|x| &x.ty).collect::<Vec<_>>(), &x.output)
+            },
+            _ => return err_with(self, "not a function type"),
+        };
+        let input = match inputs.as_slice() {
+            [] => KType::Unit,
+            [x] => ty::scan(x)?,
+            _ => return err_with(self, "only 1 input argument is supported (put arguments in a struct)"),
+        };
+        let output = match output {
+            syn::ReturnType::Default => return err_with(self, "output type must be specified explicitly"),
+            syn::ReturnType::Type(_,x) => ty::scan(x)?,
+        };
+        Ok((input,output))
+    }
+}
+
 #[ext(name=ItemEnumScan)]
 impl syn::ItemEnum {
     fn scan(&self) -> Result<KItem> {
-        if self.generics.params.len() > 0 { return err_with(&self.generics, "generic parameter is not supported") }
+        let mut attrs = self.attrs.scan()?;
         if self.has_no_variant_with_payload() {
             // Constant set.
+            if self.generics.params.len() > 0 { return err_with(&self.generics, "generic parameter is not supported on enum without variant data") }
+            if attrs.has_sum_type_form() { return err_with(self, "serialization form attribute is not supported on enum without variant data") }
+            let repr = self.attrs.scan_enum_repr(self)?;
+            let mut cases = self.variants.iter().map_collect_result(syn::Variant::scan_enum_type_case)?;
+            if repr.is_some() {
+                // Implicit discriminant is one more than previous one like Rust does.
+                let mut next = 0;
+                for x in cases.iter_mut() {
+                    let value = x.value.unwrap_or(next);
+                    x.value = Some(value);
+                    next = value + 1;
+                }
+            }
             Ok(KItem::Enum(KEnumType {
                 span: self.span().scan(),
                 name: self.ident.to_string(),
+                attrs: attrs,
+                repr: repr,
                 comment: self.attrs.scan_doc_comment()?,
-                cases: self.variants.iter().map_collect_result(syn::Variant::scan_enum_type_case)?,
+                cases: cases,
             }))
         }
         else {
@@ -123,8 +175,9 @@ impl syn::ItemEnum {
             Ok(KItem::Sum(KSumType {
                 span: self.span().scan(),
                 name: self.ident.to_string(),
-                serialization: KSumTypeSerializationForm::NameBased,
-                attrs: self.attrs.scan()?,
+                type_params: self.generics.scan_type_params()?,
+                serialization: attrs.take_sum_type_form(self.span().scan())?,
+                attrs: attrs,
                 comment: self.attrs.scan_doc_comment()?,
                 variants: self.variants.iter().map_collect_result(syn::Variant::scan_sum_type_variant)?,
             }))
@@ -143,25 +196,97 @@ impl syn::Variant {
         Ok(KEnumTypeCase {
             span: self.span().scan(),
             name: self.ident.to_string(),
+            attrs: self.attrs.scan()?,
             comment: self.attrs.scan_doc_comment()?,
+            value: match &self.discriminant {
+                None => None,
+                Some((_,x)) => Some(scan_discriminant(x)?),
+            },
         })
     }
     fn scan_sum_type_variant(&self) -> Result<KSumTypeVariant> {
         use syn::Fields::*;
-        if self.fields.len() != 1 { return err_with(&self.fields, "only single variant field is supported (make an explicitly named struct to store multiple fields)") }
-        let unnamed_fields = match &self.fields {
-            Named(_) => return err_with(&self.fields, "only unnamed field is supported (no support for named field)"),
-            Unnamed(x) => &x.unnamed,
-            Unit => return err_with(&self.fields, "all sum-type variant must have a data")
+        if let Some((_,x)) = &self.discriminant { return err_with(x, "discriminant is not supported on enum with variant data") }
+        let (content,form) = match &self.fields {
+            Unnamed(x) if x.unnamed.len() == 1 => (ty::scan(&x.unnamed[0].ty)?, KSumTypeVariantForm::Newtype),
+            Unnamed(x) => (KType::Unit, KSumTypeVariantForm::Tuple(x.unnamed.iter().map_collect_result(|x| ty::scan(&x.ty))?)),
+            Named(x) => (KType::Unit, KSumTypeVariantForm::Struct(x.named.iter().map_collect_result(syn::Field::scan_prod_type_field)?)),
+            Unit => (KType::Unit, KSumTypeVariantForm::Unit),
         };
-        if unnamed_fields.len() != 1 { return err_with(&unnamed_fields, "only single field is supported in sum-type variant") }
-        let first_unnamed_field = &unnamed_fields[0];
         Ok(KSumTypeVariant {
             span: self.span().scan(),
             name: self.ident.to_string(),
             attrs: self.attrs.scan()?,
             comment: self.attrs.scan_doc_comment()?,
-            content: ty::scan(&first_unnamed_field.ty)?,
+            content: content,
+            form: form,
+        })
+    }
+}
+
+/// Scans integer literal discriminant. (e.g. `1` or `-1`)
+fn scan_discriminant(x:&syn::Expr) -> Result<i64> {
+    const BAD_FORM_ERR: &str = "only integer literal is supported as discriminant";
+    let (neg,lit) = match x {
+        syn::Expr::Lit(x) => (false, &x.lit),
+        syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => match &**expr {
+            syn::Expr::Lit(x) => (true, &x.lit),
+            _ => return err_with(x, BAD_FORM_ERR),
+        },
+        _ => return err_with(x, BAD_FORM_ERR),
+    };
+    let value = match lit {
+        syn::Lit::Int(x) => match x.base10_parse::<i64>() {
+            Ok(x) => x,
+            Err(_) => return err_with(x, "discriminant out of range"),
+        },
+        _ => return err_with(x, BAD_FORM_ERR),
+    };
+    Ok(if neg { -value } else { value })
+}
+
+#[ext(name=ItemTraitScan)]
+impl syn::ItemTrait {
+    /// Scans a service.
+    /// Each method becomes an operation.
+    fn scan(&self) -> Result<KItem> {
+        let x = ir::scan_trait(self)?;
+        let mut fns = Vec::new();
+        for item in x.items.iter() {
+            match item {
+                ir::TraitItem::Fn(x) => fns.push(x),
+                ir::TraitItem::Unknown(x) => return err(x.span, &x.message),
+            }
+        }
+        // IR attributes lose spans and doc comments, so they are scanned again from methods in same order.
+        let methods = self.items.iter().filter_map(|x| if let syn::TraitItem::Method(x) = x { Some(x) } else { None });
+        Ok(KItem::Service(KService {
+            span: x.span,
+            name: x.name,
+            attrs: self.attrs.scan()?,
+            comment: self.attrs.scan_doc_comment()?,
+            operations: fns.iter().zip(methods).map_collect_result(|(x,method)| x.scan_operation(&method.attrs))?,
+        }))
+    }
+}
+#[ext(name=FnScan)]
+impl ir::Fn {
+    fn scan_operation(&self, attrs:&Vec<syn::Attribute>) -> Result<KOperation> {
+        let input = match self.input.as_slice() {
+            [] => KType::Unit,
+            [x] => ty::scan_type(x)?,
+            [_, x, ..] => return err(x.span, "only 1 input argument is supported (put arguments in a struct)"),
+        };
+        Ok(KOperation {
+            span: self.span,
+            name: self.name.clone(),
+            attrs: attrs.scan()?,
+            comment: attrs.scan_doc_comment()?,
+            input: input,
+            output: match &self.output {
+                None => KType::Unit,
+                Some(x) => ty::scan_type(x)?,
+            },
         })
     }
 }
@@ -170,16 +295,25 @@ impl syn::Variant {
 impl syn::ItemStruct {
     fn scan(&self) -> Result<KItem> {
         use syn::Fields::*;
-        if self.generics.params.len() > 0 { return err_with(&self.generics, "generic parameter is not supported") }
-        match &self.fields {
-            Unnamed(_) => return err_with(&self.fields, "only named fields are supported in struct (no support for unnamed fields)"),
-            Named(_) => (),
-            Unit => (),
+        let attrs = self.attrs.scan()?;
+        if attrs.has_sum_type_form() { return err_with(self, "serialization form attribute is supported only on enum") }
+        if let Unnamed(x) = &self.fields {
+            // Nominal new-type.
+            if x.unnamed.len() != 1 { return err_with(&self.fields, "only single-field tuple struct is supported (make fields named to store multiple fields)") }
+            return Ok(KItem::Nominal(KNominalType {
+                span: self.span().scan(),
+                name: self.ident.to_string(),
+                type_params: self.generics.scan_type_params()?,
+                attrs: attrs,
+                comment: self.attrs.scan_doc_comment()?,
+                origin: ty::scan(&x.unnamed[0].ty)?,
+            }))
         }
         Ok(KItem::Prod(KProdType {
             span: self.span().scan(),
             name: self.ident.to_string(),
-            attrs: self.attrs.scan()?,
+            type_params: self.generics.scan_type_params()?,
+            attrs: attrs,
             comment: self.attrs.scan_doc_comment()?,
             fields: self.fields.iter().map_collect_result(syn::Field::scan_prod_type_field)?,
         }))
@@ -195,7 +329,7 @@ impl syn::Field {
         };
         Ok(KProdTypeField {
             span: self.span().scan(),
-            name: ident.to_string(),
+            name: ident.unraw().to_string(),
             attrs: self.attrs.scan()?,
             comment: self.attrs.scan_doc_comment()?,
             content: ty::scan(&self.ty)?,
@@ -203,12 +337,20 @@ impl syn::Field {
     }
 }
 
-// #[ext(name=ItemTraitScan)]
-// impl syn::ItemTrait {
-//     fn scan(&self) -> KType {
-        
-//     }
-// }
+#[ext(name=GenericsScan)]
+impl syn::Generics {
+    /// Scans names of type parameters.
+    /// Lifetime parameters and bounds will be ignored.
+    fn scan_type_params(&self) -> Result<Vec<String>> {
+        self.params.iter().map_collect_optional_result(|x| {
+            match x {
+                syn::GenericParam::Type(x) => Ok(Some(x.ident.to_string())),
+                syn::GenericParam::Lifetime(_) => Ok(None),
+                syn::GenericParam::Const(x) => err_with(x, "const generic parameter is not supported"),
+            }
+        })
+    }
+}
 
 // #[ext(name=ItemUnionScan)]
 // impl syn::ItemUnion {
@@ -240,7 +382,8 @@ impl Vec<syn::Attribute> {
     fn scan_doc_comment(&self) -> Result<String> {
         let mut z = String::new();
         let mut f = false;
-        for x in self.iter() {
+        // Other attributes between doc comments do not make lines.
+        for x in self.iter().filter(|x| x.path.ident_string_or_default() == "doc") {
             z.push_str(&x.scan_doc_comment()?);
             z.push_str("\n");
             f = true;
@@ -1,44 +1,75 @@
-use crate::prelude::*;
 use crate::model::*;
+use crate::model::log::*;
 use super::{Result, err, ir};
 
 /// Scans a simplified type expression for serialization schema.
 /// - `Path` becomes to a reference to an explicit type.
 ///   - `Path` to `Option` type will be recognized as RIDL optional.
+///   - Type arguments are scanned recursively to any depth.
 /// - `References`, `Paren`, `Ptr` will be stripped away.
+/// - `Box`, `Rc`, `Arc` become a RIDL box. They are transparent in serialized form.
+/// - `Cow` will be stripped away.
 /// - `Array`, `Slice` becomes a RIDL array.
+/// - `HashMap`, `BTreeMap` becomes a RIDL map.
+/// - `HashSet`, `BTreeSet` becomes a RIDL set.
 /// - Everything else is not supported and returns an `Err`.
 pub fn scan(x:&syn::Type) -> Result<KType> {
     scan_type(&ir::scan_type(x)?)
 }
 
-fn scan_type(x:&ir::Type) -> Result<KType> {
+/// Scans a type expression in scanner IR.
+pub(super) fn scan_type(x:&ir::Type) -> Result<KType> {
     match x.name.as_str() {
         "Vec" => {
             if x.params.len() == 1 {} else { return err(x.span, "`Vec` type must have one parameter") }
             let p = x.params.first().unwrap();
-            Ok(KType::Vector(scan_scalar_type(&p)?))
+            Ok(KType::Vector(Box::new(scan_type(&p)?)))
+        },
+        "HashMap" | "BTreeMap" => {
+            if x.params.len() == 2 {} else { return err(x.span, "map type must have two parameters") }
+            Ok(KType::Map(Box::new(scan_type(&x.params[0])?), Box::new(scan_type(&x.params[1])?)))
+        },
+        "HashSet" | "BTreeSet" => {
+            if x.params.len() == 1 {} else { return err(x.span, "set type must have one parameter") }
+            let p = x.params.first().unwrap();
+            Ok(KType::Set(Box::new(scan_type(&p)?)))
+        },
+        "Box" | "Rc" | "Arc" => {
+            if x.params.len() == 1 {} else { return err(x.span, "pointer type must have one parameter") }
+            let p = x.params.first().unwrap();
+            Ok(KType::Boxed(Box::new(scan_type(&p)?)))
+        },
+        "Cow" => {
+            if x.params.len() == 1 {} else { return err(x.span, "`Cow` type must have one parameter") }
+            scan_type(x.params.first().unwrap())
         },
         "Option" => {
             if x.params.len() == 1 {} else { return err(x.span, "`Option` type must have one parameter") }
             let p = x.params.first().unwrap();
-            Ok(KType::Option(scan_scalar_type(&p)?))
+            Ok(KType::Option(Box::new(scan_type(&p)?)))
         },
-        _ => {
-            Ok(KType::Scalar(scan_scalar_type(x)?))
-        }
-    }
-}
-
-fn scan_scalar_type(x:&ir::Type) -> Result<KScalarType> {
-    if !x.params.is_empty() { return err(x.span, "scalar type with generic parameter is not supported") }
-    match x.name.as_str() {
-        "()" => Ok(KScalarType::Unit),
-        "bool" => Ok(KScalarType::Prim(KPrimType::Bool)),
-        "i32" => Ok(KScalarType::Prim(KPrimType::I32)),
-        "f64" => Ok(KScalarType::Prim(KPrimType::F64)),
-        "str" => Ok(KScalarType::Prim(KPrimType::String)),
-        "String" => Ok(KScalarType::Prim(KPrimType::String)),
-        _ => Ok(KScalarType::Def(x.name.clone())),
+        _ if !x.params.is_empty() => {
+            Ok(KType::App(x.name.clone(), x.params.map_collect_result(scan_type)?))
+        },
+        "()" => Ok(KType::Unit),
+        "bool" => Ok(KType::Prim(KPrimType::Bool)),
+        "i8" => Ok(KType::Prim(KPrimType::I8)),
+        "i16" => Ok(KType::Prim(KPrimType::I16)),
+        "i32" => Ok(KType::Prim(KPrimType::I32)),
+        "i64" => Ok(KType::Prim(KPrimType::I64)),
+        "i128" => Ok(KType::Prim(KPrimType::I128)),
+        "isize" => Ok(KType::Prim(KPrimType::ISize)),
+        "u8" => Ok(KType::Prim(KPrimType::U8)),
+        "u16" => Ok(KType::Prim(KPrimType::U16)),
+        "u32" => Ok(KType::Prim(KPrimType::U32)),
+        "u64" => Ok(KType::Prim(KPrimType::U64)),
+        "u128" => Ok(KType::Prim(KPrimType::U128)),
+        "usize" => Ok(KType::Prim(KPrimType::USize)),
+        "f32" => Ok(KType::Prim(KPrimType::F32)),
+        "f64" => Ok(KType::Prim(KPrimType::F64)),
+        "char" => Ok(KType::Prim(KPrimType::Char)),
+        "str" => Ok(KType::Prim(KPrimType::String)),
+        "String" => Ok(KType::Prim(KPrimType::String)),
+        _ => Ok(KType::Def(x.name.clone())),
     }
 }
@@ -7,7 +7,7 @@
 //!     #[derive(RIDL)]
 //!     #[rest(in)]
 //!     pub struct OrderItemGetInput {
-//!         #[path] pub id: String,
+//!         #[path_param] pub id: String,
 //!         #[query] pub deep: bool,
 //!     }
 //!
@@ -37,6 +37,19 @@
 //!     pub struct Order;
 //!     pub struct OrderForm;
 //!     pub struct APIError;
+//!
+//! Type aliases, function types and trait methods cannot derive.
+//! Attributes of them are attribute macros which do nothing.
+//!
+//!     use ridl_derive::{rest, int64};
+//!
+//!     #[int64(string)]
+//!     pub type OrderId = u64;
+//!
+//!     #[rest(GET, "/order/item/{id}")]
+//!     pub type OrderItemGet = dyn Fn(OrderItemGetInput) -> OrderItemGetOutput;
+//!
+//! Built-in `#[path]` attribute cannot be a helper, so path parameters are marked with `#[path_param]`.
 //! 
 
 extern crate proc_macro;
@@ -48,7 +61,7 @@ use proc_macro::TokenStream;
 /// 
 /// There are multiple discussions related to this issue.
 /// - https://github.com/rust-lang/rust/issues/65823
-#[proc_macro_derive(RIDL, attributes(rest, input, output, location, status, mime))]
+#[proc_macro_derive(RIDL, attributes(rest, input, output, location, status, mime, path_param, query, header, cookie, body, int64))]
 pub fn ridl(_item: TokenStream) -> TokenStream {
     TokenStream::new()
 }
@@ -58,6 +71,11 @@ pub fn rest(_attr:TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+#[proc_macro_attribute]
+pub fn int64(_attr:TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
 
 // !     #[rest(GET="/order/item")]
 // !     type OrderItemGet = dyn Fn(OrderItemGetInput) -> OrderItemGetOutput;
@@ -25,6 +25,13 @@ cat tmp/rust1.rs | $RUN openapi3 $OPT > tmp/openapi3/spec.yaml
 
 mkdir -p tmp/swift5
 cat tmp/rust1.rs | $RUN swift5 $OPT > tmp/swift5/main.swift
+cat tmp/rust1.rs | $RUN swift5-client $OPT > tmp/swift5/client.swift
 
 mkdir -p tmp/typescript4
 cat tmp/rust1.rs | $RUN typescript4 $OPT > tmp/typescript4/index.ts
+cat tmp/rust1.rs | $RUN typescript4-client $OPT > tmp/typescript4/client.ts
+
+mkdir -p tmp/rust
+cat tmp/rust1.rs | $RUN rust $OPT > tmp/rust/types.rs
+cat tmp/rust1.rs | $RUN rust-server $OPT > tmp/rust/server.rs
+cat tmp/rust1.rs | $RUN rust-client $OPT > tmp/rust/client.rs
@@ -0,0 +1,4 @@
+#[serde(tag="type")]
+enum Fish {
+    Tuna(String),
+}
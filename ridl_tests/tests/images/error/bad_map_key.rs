@@ -0,0 +1,6 @@
+struct Fish {
+    weights: HashMap<Scale, f64>,
+}
+struct Scale {
+    unit: String,
+}
@@ -0,0 +1,3 @@
+type Chain = Option<Box<Link>>;
+
+type Link = Vec<Chain>;
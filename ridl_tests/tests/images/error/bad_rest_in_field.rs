@@ -0,0 +1,9 @@
+#[rest(in)]
+struct Pet {
+    #[query]
+    name: String,
+    age: i32,
+}
+
+#[rest(GET,"/pets")]
+type FindPet = dyn Fn(Pet) -> String;
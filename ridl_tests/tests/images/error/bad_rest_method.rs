@@ -0,0 +1,2 @@
+#[rest(FETCH,"/pet/dish")]
+type PetDish = dyn Fn(Pet) -> Dish;
@@ -0,0 +1,9 @@
+#[rest(out)]
+enum Dish {
+    #[status(200)]
+    #[mime("application/json")]
+    Sushi(String),
+    #[status(200)]
+    #[mime("application/json")]
+    Steak(String),
+}
@@ -0,0 +1,8 @@
+#[rest(out)]
+enum Dish {
+    #[status(200)]
+    #[mime("application/json")]
+    Sushi(String),
+    #[mime("application/json")]
+    Steak(String),
+}
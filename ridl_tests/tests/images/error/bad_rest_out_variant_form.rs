@@ -0,0 +1,9 @@
+#[rest(out)]
+enum Dish {
+    #[status(200)]
+    #[mime("application/json")]
+    Sushi(String, String),
+    #[status(201)]
+    #[mime("application/json")]
+    Steak { weight: u32 },
+}
@@ -0,0 +1,12 @@
+#[rest(in)]
+struct Pet {
+    #[path]
+    name: String,
+    #[query]
+    age: i32,
+}
+
+trait PetService {
+    #[rest(GET,"/pets/{name}/ages/{age}")]
+    fn find(&self, pet: Pet) -> Pet;
+}
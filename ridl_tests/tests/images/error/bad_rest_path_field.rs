@@ -0,0 +1,8 @@
+#[rest(in)]
+struct Pet {
+    #[path]
+    name: String,
+}
+
+#[rest(GET,"/pets")]
+type FindPet = dyn Fn(Pet) -> String;
@@ -0,0 +1,13 @@
+#[derive(Serialize, Deserialize)]
+struct Address {
+    city: String,
+}
+
+#[rest(in)]
+struct Pet {
+    #[path]
+    address: Option<Address>,
+}
+
+#[rest(GET,"/pets/{address}")]
+type FindPet = dyn Fn(Pet) -> String;
@@ -0,0 +1,5 @@
+#[derive(Serialize_repr, Deserialize_repr)]
+enum Level {
+    Low = 1,
+    High = 2,
+}
@@ -0,0 +1,2 @@
+#[derive(Serialize, Deserialize)]
+struct Point(f64, f64);
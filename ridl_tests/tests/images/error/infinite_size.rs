@@ -0,0 +1,9 @@
+#[derive(Serialize, Deserialize)]
+struct Chain {
+    link: Link,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Link {
+    chain: Chain,
+}
@@ -0,0 +1,21 @@
+#[derive(Serialize, Deserialize)]
+struct Note {
+    text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[rest(in)]
+struct NoteUpload {
+    #[path_param]
+    id: String,
+    #[body]
+    #[mime("application/json")]
+    note: Option<Note>,
+    #[body]
+    #[mime("text/plain")]
+    text: Option<String>,
+}
+
+/// Replaces a note with JSON or plain text.
+#[rest(PUT,"/notes/{id}")]
+type PutNote = dyn Fn(NoteUpload) -> ();
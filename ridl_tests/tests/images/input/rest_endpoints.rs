@@ -0,0 +1,56 @@
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+#[rest(in)]
+struct OrderQuery {
+    #[path]
+    order_id: String,
+    /// Include shipped items.
+    #[query]
+    shipped: Option<bool>,
+    #[header("X-Request-Id")]
+    request_id: String,
+    #[header]
+    if_none_match: Option<String>,
+    #[cookie("session")]
+    session_token: String,
+    #[body]
+    #[mime("application/json")]
+    note: Note,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Note {
+    text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[rest(out)]
+enum OrderResult {
+    #[status(200)]
+    #[mime("application/json")]
+    Found(Note),
+    #[status(200)]
+    #[mime("text/plain")]
+    FoundText(String),
+    /// Order is being moved.
+    #[status(303)]
+    #[header("Location")]
+    #[header("Retry-After")]
+    Moved,
+    /// No such order.
+    #[status(404)]
+    Missing,
+    /// Unexpected failure.
+    #[status(default)]
+    #[mime("application/json")]
+    Failure(Note),
+}
+
+/// Finds an order.
+#[rest(POST,"/orders/{order_id}")]
+type FindOrder = dyn Fn(OrderQuery) -> OrderResult;
+
+trait OrderService {
+    #[rest(DELETE,"/orders")]
+    fn clear(&self);
+}
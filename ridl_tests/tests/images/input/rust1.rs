@@ -6,7 +6,7 @@ type Tuna = String;
 type Salmon = String;
 
 /// Ingredients of magic.
-#[serde(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all="camelCase")]
 enum Mineral {
     IronMetal,
@@ -17,29 +17,32 @@ enum Mineral {
 // #[rest(GET,"/pet/dish")]
 // type pet_dish = dyn Fn(Pet) -> Dish;
 
-#[serde(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all="camelCase")]
 #[rest(in)]
 struct Pet {
+    #[query]
     name: Vec<String>,
     /// Did they take a walk today?
     #[query]
     walk: bool,
     #[path]
-    living_address: Option<Address>,
+    living_address: String,
+    #[header("X-Request-Id")]
+    request_id: Option<String>,
     #[body]
     #[mime("application/octet-stream")]
     contents: Vec<u8>,
 }
 
-#[serde(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all="camelCase")]
 struct Address {
     city: String,
 }
 
 /// Edibles.
-#[serde(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all="camelCase")]
 #[rest(out)]
 enum Dish {
@@ -52,12 +55,187 @@ enum Dish {
     PanFriedSteak(Salmon),
 }
 
-#[serde(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all="camelCase")]
 struct APIError {
     message: String,
+    #[serde(default)]
+    error_code: i32,
+    #[serde(skip)]
+    backtrace: String,
+}
+
+/// Drawable shapes.
+#[derive(Serialize, Deserialize)]
+#[serde(tag="type")]
+enum Shape {
+    Circle(Circle),
+    Square(Square),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Circle {
+    radius: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Square {
+    side: f64,
+}
+
+/// Paint on a canvas.
+#[derive(Serialize, Deserialize)]
+#[serde(tag="t", content="c")]
+enum Paint {
+    Fill(Shape),
+    Stroke(f64),
+}
+
+/// Any measurement.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Measure {
+    Length(f64),
+    Label(String),
 }
 
+/// A page of items.
+#[derive(Serialize, Deserialize)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Pixel {
+    red: u8,
+    green: u16,
+    blue: u32,
+    offset: i8,
+    depth: i16,
+    alpha: f32,
+    glyph: char,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Ledger {
+    #[int64(string)]
+    #[serde(with="serde_with::rust::display_fromstr")]
+    id: u64,
+    #[int64(bigint)]
+    created_at: i64,
+    #[int64(number)]
+    entries: Vec<usize>,
+}
+
+/// Time in milliseconds.
+#[int64(bigint)]
+type Millis = i64;
+
+/// Arithmetic expression tree.
+#[derive(Serialize, Deserialize)]
+enum Expr {
+    Literal(f64),
+    Negate(Box<Expr>),
+    Label(Cow<'static, str>),
+    Shared(Arc<Circle>),
+    Fallback(Option<Rc<Expr>>),
+}
+
+#[derive(Serialize, Deserialize)]
+enum Tree {
+    Leaf(f64),
+    Node(Box<Branch>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Branch {
+    left: Tree,
+    right: Option<Tree>,
+}
+
+/// Opaque identifier of a user.
+#[derive(Serialize, Deserialize)]
+struct UserId(String);
+
+#[derive(Serialize, Deserialize)]
+struct Catalog {
+    owner: UserId,
+    circles: Page<Circle>,
+    squares: Option<Page<Square>>,
+    prices: HashMap<String, f64>,
+    stocks: BTreeMap<Mineral, i32>,
+    tags: HashSet<String>,
+    aliases: Option<Vec<String>>,
+    grid: Vec<Vec<f64>>,
+    slots: Vec<Option<Circle>>,
+    notes: HashMap<String, Vec<String>>,
+}
+
+/// A move in a game.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+enum Move {
+    Resign,
+    #[serde(rename_all="camelCase")]
+    Step { to_address: Address, at: i32 },
+    Pair(Circle, Square),
+    Jump(Address),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag="kind")]
+enum Signal {
+    Quiet,
+    Beep {
+        pitch: f64,
+        #[serde(skip_serializing_if="Option::is_none")]
+        note: Option<String>,
+    },
+    Tone(Square),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag="t", content="c")]
+enum Gesture {
+    Wave,
+    Point(f64, f64),
+    Swipe { from: Circle, to: Circle },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Reading {
+    Missing,
+    Pair(f64, f64),
+    Detailed { value: f64, unit: String },
+    Raw(String),
+}
+
+/// Signal strength.
+#[derive(Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+enum Level {
+    Low = 1,
+    Mid,
+    High = 10,
+}
+
+/// Pet care.
+trait PetService {
+    /// Serves a dish for a pet.
+    #[rest(POST,"/pets/{livingAddress}/feed")]
+    fn feed(&self, pet: Pet) -> Dish;
+    fn walk(&self, address: Address);
+    #[rest(GET,"/minerals")]
+    fn minerals(&self) -> Vec<Mineral>;
+}
+
+/// Serves a dish for a pet.
+#[rest(PUT,"/pets/{livingAddress}/dish")]
+type PetDish = dyn Fn(Pet) -> Dish;
+type Weigh = fn(Circle) -> f64;
+
 mod mountain {
     
 }
@@ -0,0 +1,13 @@
+#[serde(untagged)]
+enum Fish {
+    Tuna(Tuna),
+    Salmon(Salmon),
+    Weight(f64),
+}
+struct Tuna {
+    name: String,
+}
+struct Salmon {
+    name: String,
+    color: String,
+}
@@ -0,0 +1,8 @@
+#[derive(Serialize, Deserialize)]
+struct Event {
+    #[int64(number)]
+    timestamp: i64,
+    #[int64(string)]
+    #[serde(with="string")]
+    id: u64,
+}
@@ -1,14 +1,30 @@
 #![cfg(test)]
 
 mod rest;
+mod rust_rest;
+/// Rendered Rust types compile with `ridl_derive`.
+#[allow(dead_code)]
+#[path="images/output/rust"]
+mod rust_types;
 
 use ::ridl::*;
 // use crate::model;
 use crate::scan::*;
 use crate::render;
 use crate::render::openapi3::render_openapi3;
-use crate::render::swift5::render_swift5;
-use crate::render::typescript4::render_typescript4;
+use crate::render::swift5::{render_swift5,render_swift5_client};
+use crate::render::typescript4::{render_typescript4,render_typescript4_client};
+use crate::render::rust::{render_rust,render_rust_server,render_rust_client};
+
+/// Scans and applies `serde` attributes like the command does.
+fn scan_image(a:&str) -> model::KMod {
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    let mut m = scan(&b).unwrap();
+    m.retain_only_non_skipped_members();
+    m.rename(&model::rename::Options::default());
+    m.encode_int64(None);
+    m
+}
 
 #[test]
 fn test_scan_model() {
@@ -17,6 +33,16 @@ fn test_scan_model() {
     scan(&b).unwrap();
 }
 #[test]
+fn test_scan_doc_comment() {
+    let a = "/// A\n#[derive(Serialize)]\n///\n/// B\n#[serde(rename_all=\"camelCase\")]\nstruct X {}";
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    let m = scan(&b).unwrap();
+    match &m.items[0] {
+        model::KItem::Prod(x) => assert_eq!(x.comment, "A\n\nB"),
+        _ => panic!("expected a prod-type"),
+    }
+}
+#[test]
 fn test_model_serde_roundtripping() {
     let a = include_str!("images/input/rust1.rs");
     let b = syn::parse_str::<syn::File>(&a).unwrap();
@@ -25,39 +51,522 @@ fn test_model_serde_roundtripping() {
     let c = serde_yaml::from_str::<model::KMod>(&x).unwrap();
     assert_eq!(m, c);
 }
+#[test]
+fn test_model_legacy_ridl1_migration() {
+    let x = include_str!("images/legacy/ridl1");
+    let m = serde_yaml::from_str::<model::KMod>(&x).unwrap();
+    match &m.items[0] {
+        model::KItem::New(x) => assert_eq!(x.origin, model::KType::Prim(model::KPrimType::String)),
+        _ => panic!("expected a new-type"),
+    }
+    let x = render::ridl1::render_ridl1(&m).unwrap();
+    assert!(!x.contains("Scalar"));
+}
 
 #[test]
 fn test_render_openapi3() {
-    let a = include_str!("images/input/rust1.rs");
-    let b = syn::parse_str::<syn::File>(&a).unwrap();
-    let m = scan(&b).unwrap();
+    let m = scan_image(include_str!("images/input/rust1.rs"));
     let x = render_openapi3(&m).unwrap().trim().to_owned();
     let z = include_str!("images/output/openapi3").trim();
     assert_eq!(x, z);
 }
 #[test]
 fn test_render_swift5() {
-    let a = include_str!("images/input/rust1.rs");
-    let b = syn::parse_str::<syn::File>(&a).unwrap();
-    let m = scan(&b).unwrap();
+    let m = scan_image(include_str!("images/input/rust1.rs"));
     let x = render_swift5(&m).unwrap().trim().to_owned();
     let z = include_str!("images/output/swift5").trim();
     assert_eq!(x, z);
 }
 #[test]
+fn test_render_swift5_new_type_variants_wire_form() {
+    let a = r#"
+        #[derive(Serialize, Deserialize)]
+        enum Dish {
+            Sushi(Tuna),
+        }
+    "#;
+    let m = scan_image(a);
+    let x = render_swift5(&m).unwrap();
+    // Serde sends `{"Sushi":…}`. Synthesized conformance would expect `{"Sushi":{"_0":…}}`.
+    assert!(x.contains("case .Sushi: let x: Tuna = try .init(from: payload); self = .Sushi(x)"));
+    assert!(x.contains("try x.encode(to: container.superEncoder(forKey: .Sushi))"));
+    assert!(!x.contains("_0"));
+}
+#[test]
+fn test_render_swift5_client() {
+    let m = scan_image(include_str!("images/input/rust1.rs"));
+    let x = render_swift5_client(&m).unwrap().trim().to_owned();
+    let z = include_str!("images/output/swift5_client").trim();
+    assert_eq!(x, z);
+}
+#[test]
+fn test_render_swift5_client_rest() {
+    let m = scan_image(include_str!("images/input/rest_endpoints.rs"));
+    let x = render_swift5_client(&m).unwrap().trim().to_owned();
+    assert_eq!(x, include_str!("images/output/swift5_client_rest_endpoints").trim());
+}
+#[test]
+fn test_render_swift5_client_body_alternatives() {
+    let m = scan_image(include_str!("images/input/rest_bodies.rs"));
+    let x = render_swift5_client(&m).unwrap();
+    assert!(x.contains("if let x = input.note {"));
+    assert!(x.contains("} else if let x = input.text {"));
+}
+#[test]
 fn test_render_typescript4() {
-    let a = include_str!("images/input/rust1.rs");
-    let b = syn::parse_str::<syn::File>(&a).unwrap();
-    let m = scan(&b).unwrap();
+    let m = scan_image(include_str!("images/input/rust1.rs"));
     let x = render_typescript4(&m).unwrap().trim().to_owned();
     let z = include_str!("images/output/typescript4").trim();
     assert_eq!(x, z);
 }
+#[test]
+fn test_render_typescript4_client() {
+    let m = scan_image(include_str!("images/input/rust1.rs"));
+    let x = render_typescript4_client(&m).unwrap().trim().to_owned();
+    let z = include_str!("images/output/typescript4_client").trim();
+    assert_eq!(x, z);
+}
+#[test]
+fn test_render_typescript4_client_rest() {
+    let m = scan_image(include_str!("images/input/rest_endpoints.rs"));
+    let x = render_typescript4_client(&m).unwrap().trim().to_owned();
+    assert_eq!(x, include_str!("images/output/typescript4_client_rest_endpoints").trim());
+}
+#[test]
+fn test_render_typescript4_client_body_alternatives() {
+    let m = scan_image(include_str!("images/input/rest_bodies.rs"));
+    let x = render_typescript4_client(&m).unwrap();
+    assert!(x.contains(r#"else if (input.text != null) headers["Content-Type"] = "text/plain""#));
+    assert!(x.contains(r#"input.note != null ? encodeBody(input.note, "application/json") : input.text != null ? encodeBody(input.text, "text/plain") : undefined"#));
+}
+#[test]
+fn test_render_non_identifier_names() {
+    let a = r#"
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename_all="kebab-case")]
+        struct Order { order_id: String, is_paid: bool }
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename_all="SCREAMING-KEBAB-CASE")]
+        enum State { OnHold, Paid(Order) }
+        #[derive(Serialize, Deserialize)]
+        struct Quote { #[serde(rename="say \"hi\"")] text: String }
+    "#;
+    let m = scan_image(a);
+    let x = render_swift5(&m).unwrap();
+    assert!(x.contains("var orderId: String"));
+    assert!(x.contains(r#"case orderId = "order-id""#));
+    assert!(x.contains(r#"case "ON-HOLD": self = .onHold; return"#));
+    assert!(x.contains("case PAID(Order)"));
+    assert!(x.contains(r#"case sayHi = "say \"hi\"""#));
+    let x = render_typescript4(&m).unwrap();
+    assert!(x.contains(r#""order-id": string"#));
+    assert!(x.contains(r#""ON-HOLD" | { PAID: Order }"#));
+    assert!(x.contains(r#""say \"hi\"": string"#));
+}
+
+/// Rust code refers to Rust names, so model is not renamed like the command does.
+fn scan_image_for_rust(a:&str) -> model::KMod {
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap()
+}
+#[test]
+fn test_render_rust_server() {
+    let m = scan_image_for_rust(include_str!("images/input/rust1.rs"));
+    let x = render_rust_server(&m).unwrap().trim().to_owned();
+    let z = include_str!("images/output/rust_server").trim();
+    assert_eq!(x, z);
+}
+#[test]
+fn test_render_rust_server_extraction() {
+    let m = scan_image_for_rust(include_str!("images/input/rest_endpoints.rs"));
+    let x = render_rust_server(&m).unwrap();
+    assert!(x.contains(r#".route("/orders/{order_id}", routing::post(handle_find_order::<S>))"#));
+    assert!(x.contains(r#"order_id: parameter(required(path.get("order_id").map(String::as_str), "order_id")?)?,"#));
+    assert!(x.contains(r#"if_none_match: header_value(&headers, "ifNoneMatch").map(parameter).transpose()?,"#));
+    assert!(x.contains(r#"session_token: parameter(required(cookie_value(&headers, "session"), "session")?)?,"#));
+    assert!(x.contains(r#"OrderResult::FoundText(x) => encode_response(200, "text/plain", &x),"#));
+    assert!(x.contains("OrderResult::Moved => status_code(303).into_response(),"));
+    assert!(x.contains("Ok(StatusCode::NO_CONTENT.into_response())"));
+}
+/// Behaviours are tested in `rust_rest`.
+#[test]
+fn test_render_rust_server_rest() {
+    let m = scan_image_for_rust(include_str!("images/input/rest_endpoints.rs"));
+    let x = render_rust_server(&m).unwrap().trim().to_owned();
+    assert_eq!(x, include_str!("images/output/rust_server_rest_endpoints").trim());
+    let m = scan_image_for_rust(include_str!("images/input/rest_bodies.rs"));
+    let x = render_rust_server(&m).unwrap().trim().to_owned();
+    assert_eq!(x, include_str!("images/output/rust_server_rest_bodies").trim());
+}
+
+#[test]
+fn test_render_rust_client() {
+    let m = scan_image_for_rust(include_str!("images/input/rust1.rs"));
+    let x = render_rust_client(&m).unwrap().trim().to_owned();
+    let z = include_str!("images/output/rust_client").trim();
+    assert_eq!(x, z);
+}
+/// Behaviours are tested against the server in `rust_rest`.
+#[test]
+fn test_render_rust_client_rest() {
+    let m = scan_image_for_rust(include_str!("images/input/rest_endpoints.rs"));
+    let x = render_rust_client(&m).unwrap().trim().to_owned();
+    assert_eq!(x, include_str!("images/output/rust_client_rest_endpoints").trim());
+    let m = scan_image_for_rust(include_str!("images/input/rest_bodies.rs"));
+    let x = render_rust_client(&m).unwrap().trim().to_owned();
+    assert_eq!(x, include_str!("images/output/rust_client_rest_bodies").trim());
+}
+#[test]
+fn test_render_rust() {
+    let m = scan_image_for_rust(include_str!("images/input/rust1.rs"));
+    let x = render_rust(&m).unwrap().trim().to_owned();
+    let z = include_str!("images/output/rust").trim();
+    assert_eq!(x, z);
+}
+/// Spans point to different source code.
+fn strip_spans(x:&mut serde_json::Value) {
+    match x {
+        serde_json::Value::Object(x) => {
+            x.remove("span");
+            for x in x.values_mut() { strip_spans(x) }
+        },
+        serde_json::Value::Array(x) => for x in x.iter_mut() { strip_spans(x) },
+        _ => (),
+    }
+}
+#[test]
+fn test_render_rust_roundtripping() {
+    let images = [
+        include_str!("images/input/rust1.rs"),
+        include_str!("images/input/rest_endpoints.rs"),
+        include_str!("images/input/sum_type_variants.rs"),
+    ];
+    for a in images {
+        let m = scan_image_for_rust(a);
+        let x = render_rust(&m).unwrap();
+        let c = scan_image_for_rust(&x);
+        let mut m = serde_json::to_value(&m).unwrap();
+        let mut c = serde_json::to_value(&c).unwrap();
+        strip_spans(&mut m);
+        strip_spans(&mut c);
+        assert_eq!(m, c);
+    }
+}
+/// `ridl1` export keeps Rust names, so Rust code from it is same with the source.
+#[test]
+fn test_render_rust_from_ridl1_roundtripping() {
+    let m = scan_image_for_rust(include_str!("images/input/rust1.rs"));
+    let x = render::ridl1::render_ridl1(&m).unwrap();
+    let c = serde_yaml::from_str::<model::KMod>(&x).unwrap();
+    let x = render_rust(&c).unwrap();
+    let c = scan_image_for_rust(&x);
+    let mut m = serde_json::to_value(&m).unwrap();
+    let mut c = serde_json::to_value(&c).unwrap();
+    strip_spans(&mut m);
+    strip_spans(&mut c);
+    assert_eq!(m, c);
+}
+#[test]
+#[should_panic]
+fn test_render_rust_non_identifier_name() {
+    let m = scan_image("#[serde(rename_all=\"kebab-case\")]\nstruct Order { order_id: String }");
+    render_rust(&m).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn test_render_typescript4_u64() {
+    let m = scan_image("struct Counter { hits: u64 }");
+    render_typescript4(&m).unwrap();
+}
+
+#[test]
+fn test_scan_sum_type_variant_forms() {
+    let m = scan_image(include_str!("images/input/sum_type_variants.rs"));
+    let x = match &m.items[0] {
+        model::KItem::Sum(x) => x,
+        _ => panic!("expected a sum-type"),
+    };
+    match &x.variants[0].form {
+        model::KSumTypeVariantForm::Struct(fields) => assert_eq!(fields[0].name, "type"),
+        _ => panic!("expected a struct variant"),
+    }
+    assert_eq!(x.variants[1].form, model::KSumTypeVariantForm::Unit);
+}
+#[test]
+fn test_scan_integer_enum() {
+    let m = scan_image(include_str!("images/input/rust1.rs"));
+    let x = m.items.iter().find_map(|x| match x {
+        model::KItem::Enum(x) if x.name == "Level" => Some(x),
+        _ => None,
+    }).unwrap();
+    assert_eq!(x.repr, Some(model::KPrimType::U8));
+    assert_eq!(x.cases.iter().map(|x| x.value).collect::<Vec<_>>(), vec![Some(1), Some(2), Some(10)]);
+}
+#[test]
+#[should_panic]
+fn test_bad_service_operation() {
+    let m = "trait Pets { fn walk(&self, a: Address, b: Address); }";
+    let b = syn::parse_str::<syn::File>(m).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_func_type_multiple_inputs() {
+    let b = syn::parse_str::<syn::File>("type Walk = dyn Fn(Address, Address) -> Pet;").unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_func_type_missing_output() {
+    let b = syn::parse_str::<syn::File>("type Walk = fn(Address);").unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_method() {
+    let a = include_str!("images/error/bad_rest_method.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_path() {
+    let a = include_str!("images/error/bad_rest_path.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+fn test_render_openapi3_operations() {
+    let m = scan_image(include_str!("images/input/rest_endpoints.rs"));
+    let x = render_openapi3(&m).unwrap();
+    let doc = serde_yaml::from_str::<serde_yaml::Value>(&x).unwrap();
+    let op = &doc["paths"]["/orders/{orderId}"]["post"];
+    assert_eq!(op["operationId"], "FindOrder");
+    assert_eq!(op["parameters"][0]["$ref"], "#/components/parameters/OrderQuery.orderId");
+    assert_eq!(op["parameters"][1]["$ref"], "#/components/parameters/OrderQuery.shipped");
+    assert_eq!(op["requestBody"]["$ref"], "#/components/requestBodies/OrderQuery");
+    assert_eq!(op["responses"]["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Note");
+    assert_eq!(op["responses"]["200"]["content"]["text/plain"]["schema"]["type"], "string");
+    assert_eq!(op["responses"]["200"]["description"], "Found\nFoundText");
+    assert_eq!(op["responses"]["303"]["headers"]["Location"]["schema"]["type"], "string");
+    assert_eq!(op["responses"]["303"]["headers"]["Retry-After"]["schema"]["type"], "string");
+    assert_eq!(op["responses"]["default"]["description"], "Unexpected failure.");
+    assert_eq!(op["responses"]["404"]["description"], "No such order.");
+    assert!(op["responses"]["404"]["content"].is_null());
+    let comps = &doc["components"];
+    assert!(comps["schemas"]["OrderQuery"].is_null());
+    assert_eq!(comps["parameters"]["OrderQuery.orderId"]["in"], "path");
+    assert_eq!(comps["parameters"]["OrderQuery.orderId"]["required"], true);
+    assert_eq!(comps["parameters"]["OrderQuery.shipped"]["in"], "query");
+    assert_eq!(comps["parameters"]["OrderQuery.shipped"]["required"], false);
+    assert_eq!(comps["parameters"]["OrderQuery.requestId"]["name"], "X-Request-Id");
+    assert_eq!(comps["parameters"]["OrderQuery.requestId"]["in"], "header");
+    assert_eq!(comps["parameters"]["OrderQuery.requestId"]["required"], true);
+    assert_eq!(comps["parameters"]["OrderQuery.ifNoneMatch"]["name"], "ifNoneMatch");
+    assert_eq!(comps["parameters"]["OrderQuery.ifNoneMatch"]["required"], false);
+    assert_eq!(comps["parameters"]["OrderQuery.sessionToken"]["name"], "session");
+    assert_eq!(comps["parameters"]["OrderQuery.sessionToken"]["in"], "cookie");
+    assert_eq!(comps["requestBodies"]["OrderQuery"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Note");
+    assert_eq!(comps["requestBodies"]["OrderQuery"]["required"], true);
+    let op = &doc["paths"]["/orders"]["delete"];
+    assert_eq!(op["tags"][0], "OrderService");
+    assert!(op["requestBody"].is_null());
+    assert_eq!(op["responses"]["204"]["description"], "No Content");
+}
+#[test]
+fn test_render_openapi3_referred_message_in() {
+    let m = scan_image(r#"
+        #[rest(in)]
+        struct Lookup {
+            #[path] id: String,
+        }
+        #[rest(in)]
+        struct Unused {
+            #[path] id: String,
+        }
+        struct Batch {
+            lookups: Vec<Lookup>,
+        }
+    "#);
+    let x = render_openapi3(&m).unwrap();
+    let doc = serde_yaml::from_str::<serde_yaml::Value>(&x).unwrap();
+    let schemas = &doc["components"]["schemas"];
+    assert_eq!(schemas["Batch"]["properties"]["lookups"]["items"]["$ref"], "#/components/schemas/Lookup");
+    assert_eq!(schemas["Lookup"]["type"], "object");
+    assert!(schemas["Unused"].is_null());
+}
+#[test]
+fn test_render_renamed_path_param() {
+    let a = r#"
+        #[rest(in)]
+        struct Lookup {
+            #[serde(rename="oid")]
+            #[path]
+            order_id: String,
+        }
+        #[rest(GET,"/orders/{order_id}")]
+        type FindOrder = fn(Lookup) -> String;
+    "#;
+    let m = scan_image(a);
+    assert!(render_swift5_client(&m).unwrap().contains(r#"path: "/orders/\(try pathValue(input.oid))""#));
+    assert!(render_typescript4_client(&m).unwrap().contains("`/orders/${pathValue(input.oid)}`"));
+    assert!(render_openapi3(&m).unwrap().contains("/orders/{oid}"));
+    let m = scan_image_for_rust(a);
+    assert!(render_rust_client(&m).unwrap().contains(r#"format!("/orders/{}", path_value(&input.order_id)?)"#));
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_in_field() {
+    let a = include_str!("images/error/bad_rest_in_field.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_in_body_alternatives() {
+    let a = r#"
+        #[rest(in)]
+        struct Upload {
+            #[body] #[mime("application/json")] json: Option<String>,
+            #[body] #[mime("text/plain")] text: String,
+        }
+    "#;
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_in_body_mime() {
+    let a = r#"
+        #[rest(in)]
+        struct Upload { #[body] data: Vec<u8> }
+    "#;
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_out_duplicate_response() {
+    let a = include_str!("images/error/bad_rest_out_duplicate_response.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+fn test_bad_rest_out_variant_form() {
+    let a = include_str!("images/error/bad_rest_out_variant_form.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    let x = scan(&b).unwrap_err();
+    // Reported at the variant, not when rendering responses.
+    assert_eq!(x.0[0].span.end.line, 5);
+    assert!(x.0[0].message.contains("must be a unit or a new-type"));
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_out_missing_status() {
+    let a = include_str!("images/error/bad_rest_out_missing_status.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_path_field() {
+    let a = include_str!("images/error/bad_rest_path_field.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_rest_path_field_type() {
+    let a = include_str!("images/error/bad_rest_path_field_type.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+fn test_scan_rest_path_field_newtype() {
+    let a = r#"
+        #[derive(Serialize, Deserialize)]
+        struct PetId(u64);
+        #[rest(in)]
+        struct Pet { #[path] id: PetId }
+        #[rest(GET,"/pets/{id}")]
+        type FindPet = dyn Fn(Pet) -> String;
+    "#;
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+#[test]
+#[should_panic]
+fn test_bad_serde_repr() {
+    let a = include_str!("images/error/bad_serde_repr.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn test_bad_internally_tagged_sum_type() {
+    let a = include_str!("images/error/bad_internally_tagged_sum_type.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn test_bad_tuple_struct() {
+    let a = include_str!("images/error/bad_tuple_struct.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn test_bad_infinite_size() {
+    let a = include_str!("images/error/infinite_size.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
 
 #[test]
 #[should_panic]
-fn test_bad_rust_code() {
-    let a = include_str!("images/error/bad_sum_type.rs");
+fn test_bad_recursive_alias() {
+    let a = include_str!("images/error/bad_recursive_alias.rs");
     let b = syn::parse_str::<syn::File>(&a).unwrap();
     scan(&b).unwrap();
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_render_swift5_recursive_nominal_type() {
+    let a = r#"
+        #[derive(Serialize, Deserialize)]
+        struct Ancestor(Option<Parent>);
+        type Parent = Box<Ancestor>;
+    "#;
+    let m = scan_image(a);
+    let x = render_swift5(&m).unwrap();
+    assert!(x.contains("@Indirect var rawValue: Parent?"));
+    assert!(x.contains("enum Indirect<Value: Hashable & Codable>"));
+}
+
+#[test]
+#[should_panic]
+fn test_bad_map_key() {
+    let a = include_str!("images/error/bad_map_key.rs");
+    let b = syn::parse_str::<syn::File>(&a).unwrap();
+    scan(&b).unwrap();
+}
+
+#[test]
+fn test_lint_ambiguous_untagged_sum_type() {
+    let m = scan_image(include_str!("images/warning/ambiguous_untagged_sum_type.rs"));
+    let logs = m.lint();
+    assert_eq!(logs.len(), 1);
+    assert!(logs[0].message.contains("`Salmon`"));
+}
+#[test]
+fn test_lint_lossy_int64() {
+    let m = scan_image(include_str!("images/warning/lossy_int64.rs"));
+    let logs = m.lint();
+    assert_eq!(logs.len(), 1);
+    assert!(logs[0].message.contains("precision"));
+}
@@ -0,0 +1,71 @@
+//! `images/input/rest_bodies.rs`.
+
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+
+#[path="../images/output/rust_server_rest_bodies"]
+mod server;
+#[path="../images/output/rust_client_rest_bodies"]
+mod client;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Note {
+    text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NoteUpload {
+    id: String,
+    note: Option<Note>,
+    text: Option<String>,
+}
+
+/// Keeps the last received upload.
+#[derive(Clone, Default)]
+struct Notes(Arc<Mutex<Option<NoteUpload>>>);
+impl server::Service for Notes {
+    async fn put_note(&self, input: NoteUpload) {
+        *self.0.lock().unwrap() = Some(input);
+    }
+}
+
+fn put_note(content_type:&str, body:&'static str) -> Request<Body> {
+    Request::put("/notes/n1").header("Content-Type", content_type).body(Body::from(body)).unwrap()
+}
+
+#[tokio::test]
+async fn test_rust_server_body_alternatives() {
+    let notes = Notes::default();
+    let send = |x| super::send(server::router(notes.clone()), x);
+    assert_eq!(send(put_note("application/json; charset=utf-8", r#"{"text":"hi"}"#)).await.0, StatusCode::NO_CONTENT);
+    let x = notes.0.lock().unwrap().take().unwrap();
+    assert_eq!((x.id.as_str(), x.note, x.text), ("n1", Some(Note { text: "hi".to_string() }), None));
+    assert_eq!(send(put_note("text/plain", "hi")).await.0, StatusCode::NO_CONTENT);
+    let x = notes.0.lock().unwrap().take().unwrap();
+    assert_eq!((x.note, x.text), (None, Some("hi".to_string())));
+    assert_eq!(send(put_note("image/png", "hi")).await.0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+struct InProcess(Notes);
+impl client::Transport for InProcess {
+    type Error = std::convert::Infallible;
+    async fn send(&self, x: client::Request) -> Result<client::Response, Self::Error> {
+        let x = super::serve(server::router(self.0.clone()), x.method, &x.url, x.headers, x.body).await;
+        Ok(client::Response { status: x.status, headers: x.headers, body: x.body })
+    }
+}
+
+#[tokio::test]
+async fn test_rust_client_body_alternatives() {
+    let notes = Notes::default();
+    let client = client::Client::new("", InProcess(notes.clone()));
+    let upload = |note, text| NoteUpload { id: "n1".to_string(), note: note, text: text };
+    client.put_note(&upload(Some(Note { text: "hi".to_string() }), Some("ignored".to_string()))).await.unwrap();
+    let x = notes.0.lock().unwrap().take().unwrap();
+    assert_eq!((x.note, x.text), (Some(Note { text: "hi".to_string() }), None));
+    client.put_note(&upload(None, Some("hi".to_string()))).await.unwrap();
+    let x = notes.0.lock().unwrap().take().unwrap();
+    assert_eq!((x.note, x.text), (None, Some("hi".to_string())));
+}
@@ -0,0 +1,100 @@
+//! `images/input/rest_endpoints.rs`.
+
+use serde::{Serialize, Deserialize};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+
+#[path="../images/output/rust_server_rest_endpoints"]
+mod server;
+#[path="../images/output/rust_client_rest_endpoints"]
+mod client;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+pub struct OrderQuery {
+    order_id: String,
+    shipped: Option<bool>,
+    request_id: String,
+    if_none_match: Option<String>,
+    session_token: String,
+    note: Note,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Note {
+    text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum OrderResult {
+    Found(Note),
+    FoundText(String),
+    Moved,
+    Missing,
+    Failure(Note),
+}
+
+struct Orders;
+impl server::Service for Orders {
+    async fn find_order(&self, input: OrderQuery) -> OrderResult {
+        match input.order_id.as_str() {
+            "1" => OrderResult::Found(input.note),
+            "2" => OrderResult::FoundText(format!("{} {:?} {}", input.request_id, input.shipped, input.session_token)),
+            "3" => OrderResult::Moved,
+            "4" => OrderResult::Missing,
+            _ => OrderResult::Failure(input.note),
+        }
+    }
+    async fn clear(&self) {}
+}
+
+fn find_order(id:&str) -> Request<Body> {
+    Request::post(format!("/orders/{}?shipped=true", id))
+        .header("X-Request-Id", "r1")
+        .header("Cookie", "theme=dark; session=s1")
+        .body(Body::from(r#"{"text":"hi"}"#))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_rust_server_responses() {
+    let send = |x| super::send(server::router(Orders), x);
+    assert_eq!(send(find_order("1")).await, (StatusCode::OK, r#"{"text":"hi"}"#.to_string()));
+    assert_eq!(send(find_order("2")).await, (StatusCode::OK, "r1 Some(true) s1".to_string()));
+    assert_eq!(send(find_order("3")).await.0, StatusCode::SEE_OTHER);
+    assert_eq!(send(find_order("4")).await.0, StatusCode::NOT_FOUND);
+    assert_eq!(send(find_order("5")).await, (StatusCode::from_u16(server::DEFAULT_STATUS).unwrap(), r#"{"text":"hi"}"#.to_string()));
+    let request = Request::post("/orders/1").body(Body::from(r#"{"text":"hi"}"#)).unwrap();
+    assert_eq!(send(request).await.0, StatusCode::BAD_REQUEST);
+    let request = Request::delete("/orders").body(Body::empty()).unwrap();
+    assert_eq!(send(request).await.0, StatusCode::NO_CONTENT);
+}
+
+struct InProcess;
+impl client::Transport for InProcess {
+    type Error = std::convert::Infallible;
+    async fn send(&self, x: client::Request) -> Result<client::Response, Self::Error> {
+        let x = super::serve(server::router(Orders), x.method, &x.url, x.headers, x.body).await;
+        Ok(client::Response { status: x.status, headers: x.headers, body: x.body })
+    }
+}
+
+#[tokio::test]
+async fn test_rust_client_dispatch() {
+    let client = client::Client::new("", InProcess);
+    let query = |id:&str| OrderQuery {
+        order_id: id.to_string(),
+        shipped: Some(true),
+        request_id: "r1".to_string(),
+        if_none_match: None,
+        session_token: "s1".to_string(),
+        note: Note { text: "hi".to_string() },
+    };
+    let note = || Note { text: "hi".to_string() };
+    assert_eq!(client.find_order(&query("1")).await.unwrap(), OrderResult::Found(note()));
+    assert_eq!(client.find_order(&query("2")).await.unwrap(), OrderResult::FoundText("r1 Some(true) s1".to_string()));
+    assert_eq!(client.find_order(&query("3")).await.unwrap(), OrderResult::Moved);
+    assert_eq!(client.find_order(&query("4")).await.unwrap(), OrderResult::Missing);
+    assert_eq!(client.find_order(&query("5")).await.unwrap(), OrderResult::Failure(note()));
+    client.clear().await.unwrap();
+}
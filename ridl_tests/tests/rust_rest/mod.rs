@@ -0,0 +1,37 @@
+//! Generated Rust code compiled against types written by hand.
+//! Each module mirrors an input image without REST attributes.
+//! Clients are tested against servers in process.
+
+mod endpoints;
+mod bodies;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use tower::ServiceExt;
+
+/// Sends a request to `router` in process, and returns status with body.
+async fn send(router:Router, request:Request<Body>) -> (StatusCode, String) {
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+/// Parts of an HTTP response, as each client module has its own `Response` type.
+struct Parts {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+/// Sends a request made by a client to `router` in process.
+async fn serve(router:Router, method:&str, url:&str, headers:Vec<(String, String)>, body:Vec<u8>) -> Parts {
+    let mut request = Request::builder().method(method).uri(url);
+    for (k, v) in headers { request = request.header(k, v) }
+    let response = router.oneshot(request.body(Body::from(body)).unwrap()).await.unwrap();
+    let status = response.status().as_u16();
+    let headers = response.headers().iter().map(|(k, v)| (k.to_string(), v.to_str().unwrap().to_string())).collect();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec();
+    Parts { status: status, headers: headers, body: body }
+}
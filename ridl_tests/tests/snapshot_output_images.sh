@@ -17,4 +17,15 @@ RUN="cargo run --manifest-path ../ridl/Cargo.toml -- "
 cat tests/images/input/rust1.rs | $RUN ridl1 > tests/images/output/ridl1
 cat tests/images/input/rust1.rs | $RUN openapi3 > tests/images/output/openapi3
 cat tests/images/input/rust1.rs | $RUN swift5 > tests/images/output/swift5
+cat tests/images/input/rust1.rs | $RUN swift5-client > tests/images/output/swift5_client
 cat tests/images/input/rust1.rs | $RUN typescript4 > tests/images/output/typescript4
+cat tests/images/input/rust1.rs | $RUN typescript4-client > tests/images/output/typescript4_client
+cat tests/images/input/rust1.rs | $RUN rust > tests/images/output/rust
+cat tests/images/input/rust1.rs | $RUN rust-server > tests/images/output/rust_server
+cat tests/images/input/rest_endpoints.rs | $RUN rust-server > tests/images/output/rust_server_rest_endpoints
+cat tests/images/input/rest_bodies.rs | $RUN rust-server > tests/images/output/rust_server_rest_bodies
+cat tests/images/input/rust1.rs | $RUN rust-client > tests/images/output/rust_client
+cat tests/images/input/rest_endpoints.rs | $RUN swift5-client > tests/images/output/swift5_client_rest_endpoints
+cat tests/images/input/rest_endpoints.rs | $RUN typescript4-client > tests/images/output/typescript4_client_rest_endpoints
+cat tests/images/input/rest_endpoints.rs | $RUN rust-client > tests/images/output/rust_client_rest_endpoints
+cat tests/images/input/rest_bodies.rs | $RUN rust-client > tests/images/output/rust_client_rest_bodies